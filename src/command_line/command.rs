@@ -18,32 +18,31 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use crate::event::*;
-
-pub fn replace_macros(cmd: Vec<&str>) -> Vec<String> {
-    let mut ret: Vec<String> = cmd.clone().iter()
-        .map(|s| s.to_string()).collect();
-
-    for (i, token) in cmd.iter().enumerate() {
-        match *token {
-            "<space>" => ret[i] = String::from(" "),
-            _ => (),
-        }
-    }
-
-    ret
-}
-
-pub fn parse(cmd: &Vec<&str>) -> Option<Event> {
-    let cmd = replace_macros(cmd.clone());
-
-    match get_lowercase(&cmd, 0) {
+use super::notation;
+
+/* Every command name and alias parse knows, for completion */
+pub const NAMES: &[&str] = &[
+    "echo", "draw", "quit", "q", "exit", "switchscreen", "screen",
+    "focusnext", "focusprev", "down", "up", "select", "start", "next", "prev",
+    "top", "gotop", "gototop", "totop", "bottom", "gobottom", "gotobottom",
+    "tobottom", "bot", "gobot", "gotobot", "tobot", "gotoplaying", "playing",
+    "visual", "mark", "clearmarks", "unmarkall", "search", "s", "goto", "go",
+    "g", "to", "togglepause", "pause", "toggle", "clear", "clearqueue",
+    "delete", "del", "move", "moveup", "movedown", "saveplaylist", "save",
+    "rmplaylist", "renameplaylist", "addtoplaylist", "crop", "shuffle",
+    "dedup", "random", "repeat", "single", "consume", "play", "stop",
+    "crossfade", "xfade", "seek", "volume", "vol", "set", "bind", "bindkey",
+    "alias", "repeatlast",
+];
+
+/* Parses a command already split into arguments by tokenize */
+pub fn parse(cmd: &[String]) -> Option<Event> {
+    match get_lowercase(cmd, 0) {
         Some(s) => match s.as_str() {
-            "echo" => match cmd.get(1) {
-                Some(s) => Some(Event::ToCommandLine(CommandLineEvent::Echo(s.to_string()))),
-                None => None,
-            },
+            "echo" => get_rest(cmd, 1)
+                .map(|s| Event::ToCommandLine(CommandLineEvent::Echo(s))),
 
-            "draw" => draw(&cmd),
+            "draw" => draw(cmd),
 
             "quit"
             | "q"
@@ -79,19 +78,24 @@ pub fn parse(cmd: &Vec<&str>) -> Option<Event> {
             | "gotobot"
             | "tobot" => Some(Event::ToFocus(ComponentEvent::GoToBottom)),
 
+            "gotoplaying"
+            | "playing" => Some(Event::ToFocus(ComponentEvent::GoToPlaying)),
+
+            "visual" => Some(Event::ToFocus(ComponentEvent::Visual)),
+            "mark" => Some(Event::ToFocus(ComponentEvent::Mark)),
+
+            "clearmarks"
+            | "unmarkall" => Some(Event::ToFocus(ComponentEvent::ClearMarks)),
+
             "search"
-            | "s" => match get_lowercase(&cmd, 1) {
-                Some(s) => Some(Event::ToFocus(ComponentEvent::Search(s.to_string()))),
-                None => None,
-            },
+            | "s" => get_rest(cmd, 1)
+                .map(|s| Event::ToFocus(ComponentEvent::Search(s.to_lowercase()))),
 
             "goto"
             | "go"
             | "g"
-            | "to" => match get_usize(&cmd, 1) {
-                Some(num) => Some(Event::ToFocus(ComponentEvent::GoTo(num))),
-                None => None,
-            }
+            | "to" => get_usize(cmd, 1)
+                .map(|num| Event::ToFocus(ComponentEvent::GoTo(num))),
 
             "togglepause"
             | "pause"
@@ -100,41 +104,120 @@ pub fn parse(cmd: &Vec<&str>) -> Option<Event> {
             "clear"
             | "clearqueue" => Some(Event::ToMpd(MpdEvent::ClearQueue)),
 
+            "delete"
+            | "del" => match cmd.get(1) {
+                Some(_) => get_usize(cmd, 1)
+                    .filter(|n| *n > 0)
+                    .map(|n| Event::ToFocus(ComponentEvent::Delete(n))),
+                None => Some(Event::ToFocus(ComponentEvent::Delete(1))),
+            },
+
+            "move" => match get_lowercase(cmd, 1)?.as_str() {
+                "up" => Some(Event::ToFocus(ComponentEvent::MoveUp)),
+                "down" => Some(Event::ToFocus(ComponentEvent::MoveDown)),
+                _ => None,
+            },
+            "moveup" => Some(Event::ToFocus(ComponentEvent::MoveUp)),
+            "movedown" => Some(Event::ToFocus(ComponentEvent::MoveDown)),
+
+            "saveplaylist"
+            | "save" => get_rest(cmd, 1)
+                .map(|name| Event::ToMpd(MpdEvent::SavePlaylist(name))),
+
+            "rmplaylist" => match get_rest(cmd, 1) {
+                Some(name) => Some(Event::ToMpd(MpdEvent::RemovePlaylist(name))),
+                None => Some(Event::ToFocus(ComponentEvent::RemovePlaylist)),
+            },
+
+            "renameplaylist" => get_rest(cmd, 1)
+                .map(|name| Event::ToFocus(ComponentEvent::RenamePlaylist(name))),
+
+            "addtoplaylist" => get_rest(cmd, 1)
+                .map(|name| Event::ToFocus(ComponentEvent::AddToPlaylist(name))),
+
+            "crop" => Some(Event::ToFocus(ComponentEvent::Crop)),
+            "shuffle" => Some(Event::ToFocus(ComponentEvent::Shuffle)),
+            "dedup" => Some(Event::ToFocus(ComponentEvent::Dedup)),
+
             "random" => Some(Event::ToMpd(MpdEvent::Random)),
+            "repeat" => Some(Event::ToMpd(MpdEvent::Repeat)),
+            "single" => Some(Event::ToMpd(MpdEvent::Single)),
+            "consume" => Some(Event::ToMpd(MpdEvent::Consume)),
+            "play" => Some(Event::ToMpd(MpdEvent::Play)),
+            "stop" => Some(Event::ToMpd(MpdEvent::Stop)),
+
+            "crossfade"
+            | "xfade" => get_i64(cmd, 1)
+                .map(|secs| Event::ToMpd(MpdEvent::Crossfade(secs))),
 
+            "seek" => get_adjustment(cmd, 1, parse_time)
+                .map(|a| Event::ToMpd(MpdEvent::Seek(a))),
+
+            "volume"
+            | "vol" => get_adjustment(cmd, 1, |s| s.parse::<i64>().ok())
+                .map(|a| Event::ToMpd(MpdEvent::Volume(a))),
+
+            "set" => set(cmd),
+
+            // These only get the rest of their own command. Whoever runs
+            // them adds the commands after it on the line
             "bind"
-            | "bindkey" => match cmd.get(1) {
-                Some(s) => {
-                    let new_cmd = cmd.iter()
-                        .skip(2)
-                        .map(|s| s.as_str())
-                        .collect();
-
-                    match parse(&new_cmd) {
-                        Some(e) => match NestableEvent::from_event(e) {
-                            Some(e) => Some(Event::BindKey(s.to_string(), e)),
-                            None => None,
-                        },
-                        _ => None,
-                    }
-                },
-                None => None,
+            | "bindkey" => Some(Event::BindKey(notation::parse(cmd.get(1)?)?, body(cmd))),
+
+            "repeatlast" => match cmd.get(1) {
+                Some(_) => get_usize(cmd, 1)
+                    .map(|n| Event::ToCommandLine(CommandLineEvent::RepeatLast(Some(n)))),
+                None => Some(Event::ToCommandLine(CommandLineEvent::RepeatLast(None))),
             },
 
+            "alias" => Some(Event::ToCommandLine(CommandLineEvent::Alias(
+                cmd.get(1)?.to_string(),
+                body(cmd),
+            ))),
+
             _ => None,
         }
         None => None,
     }
 }
 
-fn get_lowercase(cmd: &Vec<String>, i: usize) -> Option<String> {
+/* commands with count given to them as an argument, for the commands that
+ * take one instead of being repeated. A count on top or bottom goes to that
+ * line, counting from 1, and a count on delete deletes that many tracks */
+pub fn with_count(commands: &[Vec<String>], count: usize) -> Option<Vec<Vec<String>>> {
+    let cmd = match commands {
+        [cmd] => cmd,
+        _ => return None,
+    };
+
+    match parse(cmd)? {
+        Event::ToFocus(ComponentEvent::GoToTop)
+        | Event::ToFocus(ComponentEvent::GoToBottom) =>
+            Some(vec![vec!["goto".to_string(), count.saturating_sub(1).to_string()]]),
+        Event::ToFocus(ComponentEvent::Delete(_)) =>
+            Some(vec![vec!["delete".to_string(), count.to_string()]]),
+        Event::ToCommandLine(CommandLineEvent::RepeatLast(_)) =>
+            Some(vec![vec!["repeatlast".to_string(), count.to_string()]]),
+        _ => None,
+    }
+}
+
+/* What bind or alias cmd runs, as far as it goes */
+fn body(cmd: &[String]) -> Vec<Vec<String>> {
+    match cmd.get(2..) {
+        Some(rest) if !rest.is_empty() => vec![rest.to_vec()],
+        _ => Vec::new(),
+    }
+}
+
+fn get_lowercase(cmd: &[String], i: usize) -> Option<String> {
     match cmd.get(i) {
         Some(s) => Some(s.to_string().to_lowercase()),
         None => None,
     }
 }
 
-fn get_usize(cmd: &Vec<String>, i: usize) -> Option<usize> {
+fn get_usize(cmd: &[String], i: usize) -> Option<usize> {
     match cmd.get(i) {
         Some(s) => match s.parse::<usize>() {
             Ok(num) => Some(num),
@@ -144,7 +227,48 @@ fn get_usize(cmd: &Vec<String>, i: usize) -> Option<usize> {
     }
 }
 
-fn _get_boolean(cmd: &Vec<String>, i: usize) -> Option<bool> {
+/* Everything from i onwards, as one string. Used for names which may have
+ * spaces in them */
+fn get_rest(cmd: &[String], i: usize) -> Option<String> {
+    match cmd.get(i..) {
+        Some(rest) if !rest.is_empty() => Some(rest.join(" ")),
+        _ => None,
+    }
+}
+
+fn get_i64(cmd: &[String], i: usize) -> Option<i64> {
+    cmd.get(i)?.parse::<i64>().ok()
+}
+
+/* Parses '+n' and '-n' as Adjustment::By and anything else as
+ * Adjustment::To, using parse to read the number itself */
+fn get_adjustment<F>(cmd: &[String], i: usize, parse: F) -> Option<Adjustment>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let s = cmd.get(i)?;
+    // Only one sign, which is what picks By or To
+    let parse = |s: &str| match s.starts_with(['+', '-']) {
+        true => None,
+        false => parse(s),
+    };
+
+    if let Some(s) = s.strip_prefix('+') {
+        parse(s).map(Adjustment::By)
+    } else if let Some(s) = s.strip_prefix('-') {
+        parse(s).map(|n| Adjustment::By(-n))
+    } else {
+        parse(s).map(Adjustment::To)
+    }
+}
+
+/* Parses seconds from 'ss', 'mm:ss' or 'hh:mm:ss' */
+fn parse_time(s: &str) -> Option<i64> {
+    s.split(':')
+        .try_fold(0, |acc, part| Some(acc * 60 + part.parse::<u32>().ok()? as i64))
+}
+
+fn _get_boolean(cmd: &[String], i: usize) -> Option<bool> {
     match cmd.get(i) {
         Some(s) => match s.to_lowercase().as_str() {
             "0" | "false" => Some(false),
@@ -154,7 +278,21 @@ fn _get_boolean(cmd: &Vec<String>, i: usize) -> Option<bool> {
     }
 }
 
-fn draw(cmd: &Vec<String>) -> Option<Event> {
+fn set(cmd: &[String]) -> Option<Event> {
+    let val = cmd.get(2)?.to_string();
+
+    match get_lowercase(cmd, 1)?.as_str() {
+        "statusline" => Some(Event::ToCommandLine(CommandLineEvent::Statusline(
+            get_rest(cmd, 2)?
+        ))),
+        "mpd_host" => Some(Event::ToApp(AppEvent::SetMpdHost(val))),
+        "mpd_port" => Some(Event::ToApp(AppEvent::SetMpdPort(val))),
+        "mpd_password" => Some(Event::ToApp(AppEvent::SetMpdPassword(val))),
+        _ => None,
+    }
+}
+
+fn draw(cmd: &[String]) -> Option<Event> {
     if let Some(component) = cmd.get(1) {
         let (max_w, max_h) = termion::terminal_size().unwrap();
 
@@ -0,0 +1,141 @@
+/* Tab completion for the commandline
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use crate::library::Library;
+use super::command;
+
+// Tags whose values search is completed with
+const SEARCH_TAGS: &[&str] = &["Artist", "AlbumArtist", "Album", "Genre"];
+
+/* What completions are drawn from, besides command names */
+pub struct Sources<'a> {
+    pub aliases: &'a [String],
+    pub components: &'a [String],
+    pub playlists: &'a [String],
+    pub library: &'a Library,
+}
+
+/* Completions being cycled through with tab */
+pub struct Cycle {
+    // Where in the line the completion goes
+    pub start: usize,
+    pub candidates: Vec<String>,
+    pub index: usize,
+}
+
+impl Cycle {
+    pub fn step(&mut self, forward: bool) {
+        let len = self.candidates.len();
+
+        self.index = match forward {
+            true => (self.index + 1) % len,
+            false => (self.index + len - 1) % len,
+        };
+    }
+
+    pub fn current(&self) -> &str {
+        &self.candidates[self.index]
+    }
+}
+
+/* Completions for the end of line. Returns where in line the text being
+ * completed starts, and what it could be replaced with */
+pub fn complete(line: &str, sources: &Sources) -> (usize, Vec<String>) {
+    // Each command after a ; is completed on its own
+    if let Some(i) = line.rfind(';') {
+        let rest = &line[i + 1..];
+        let start = line.len() - rest.trim_start().len();
+
+        let (s, candidates) = complete(&line[start..], sources);
+        return (start + s, candidates);
+    }
+
+    let (cmd, args_start) = match line.find(' ') {
+        Some(i) => (line[..i].to_lowercase(), i + 1),
+        None => return (0, matching(
+            command::NAMES.iter().copied().chain(sources.aliases.iter().map(|s| s.as_str())),
+            line,
+        )),
+    };
+
+    let args = &line[args_start..];
+    let word_start = match args.rfind(' ') {
+        Some(i) => args_start + i + 1,
+        None => args_start,
+    };
+    let word = &line[word_start..];
+
+    // Number of the argument being completed, from 1
+    let arg = args.matches(' ').count() + 1;
+
+    match (cmd.as_str(), arg) {
+        ("bind", 2..) | ("bindkey", 2..) | ("alias", 2..) => {
+            // The rest is a command of its own
+            let cmd_start = match args.find(' ') {
+                Some(i) => args_start + i + 1,
+                None => return (word_start, Vec::new()),
+            };
+
+            let (start, candidates) = complete(&line[cmd_start..], sources);
+            (cmd_start + start, candidates)
+        },
+        ("switchscreen", 1) | ("screen", 1) | ("draw", 1) => (
+            word_start,
+            matching(sources.components.iter().map(|s| s.as_str()), word),
+        ),
+        ("move", 1) => (word_start, matching(["up", "down"].iter().copied(), word)),
+        ("set", 1) => (
+            word_start,
+            matching(
+                ["statusline", "mpd_host", "mpd_port", "mpd_password"].iter().copied(),
+                word,
+            ),
+        ),
+        // These take everything after the command as one name
+        ("saveplaylist", _) | ("save", _) | ("rmplaylist", _) | ("addtoplaylist", _) => (
+            args_start,
+            matching(sources.playlists.iter().map(|s| s.as_str()), args),
+        ),
+        ("search", _) | ("s", _) => (
+            args_start,
+            matching(
+                SEARCH_TAGS.iter().flat_map(|tag| sources.library.values(tag)),
+                args,
+            ),
+        ),
+        _ => (word_start, Vec::new()),
+    }
+}
+
+/* Candidates starting with prefix, ignoring case, sorted with duplicates
+ * removed */
+fn matching<'a, I>(candidates: I, prefix: &str) -> Vec<String>
+    where I: Iterator<Item = &'a str>
+{
+    let prefix = prefix.to_lowercase();
+
+    let mut ret: Vec<String> = candidates
+        .filter(|c| c.to_lowercase().starts_with(&prefix))
+        .map(|c| c.to_string())
+        .collect();
+
+    ret.sort_unstable();
+    ret.dedup();
+    ret
+}
@@ -0,0 +1,134 @@
+/* Commandline and search history
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+
+// Entries kept for each of commands and searches
+const MAX_HISTORY: usize = 500;
+
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct History {
+    entries: Vec<String>,
+    // Entry being looked at, or None when back at the line being typed
+    pos: Option<usize>,
+    // What was being typed before going back through history
+    draft: String,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            entries: Vec::new(),
+            pos: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /* Adds entry as the newest, moving it there if it was already in */
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+
+        if entry.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|e| e != entry);
+        self.entries.push(entry.to_string());
+
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.remove(0);
+        }
+    }
+
+    /* Goes back to the line being typed */
+    pub fn reset(&mut self) {
+        self.pos = None;
+        self.draft.clear();
+    }
+
+    /* The entry before the one being looked at. current is what is typed
+     * now, which is given back by next once past the newest entry */
+    pub fn prev(&mut self, current: &str) -> Option<&str> {
+        let pos = match self.pos {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            },
+            Some(pos) => pos.saturating_sub(1),
+        };
+
+        self.pos = Some(pos);
+        Some(&self.entries[pos])
+    }
+
+    pub fn next(&mut self) -> Option<&str> {
+        match self.pos {
+            None => None,
+            Some(pos) if pos + 1 < self.entries.len() => {
+                self.pos = Some(pos + 1);
+                Some(&self.entries[pos + 1])
+            },
+            Some(_) => {
+                self.pos = None;
+                Some(&self.draft)
+            },
+        }
+    }
+}
+
+/* Reads command and search history from path. Commands are stored with a
+ * leading ':' and searches with a leading '/' */
+pub fn load(path: &str) -> io::Result<(History, History)> {
+    let mut commands = History::new();
+    let mut searches = History::new();
+
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+
+        if let Some(cmd) = line.strip_prefix(':') {
+            commands.push(cmd);
+        } else if let Some(search) = line.strip_prefix('/') {
+            searches.push(search);
+        }
+    }
+
+    Ok((commands, searches))
+}
+
+pub fn save(path: &str, commands: &History, searches: &History) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+
+    for cmd in commands.entries() {
+        writeln!(file, ":{}", cmd)?;
+    }
+
+    for search in searches.entries() {
+        writeln!(file, "/{}", search)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+/* Text being typed into the commandline, with a cursor
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct Input {
+    text: String,
+    // Byte offset into text, always on a char boundary
+    cursor: usize,
+}
+
+impl Input {
+    pub fn new() -> Input {
+        Input {
+            text: String::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /* Replaces the text, putting the cursor at the end */
+    pub fn set(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.text.len();
+    }
+
+    /* Replaces everything from start up to the cursor with text, leaving the
+     * cursor after it */
+    pub fn replace(&mut self, start: usize, text: &str) {
+        self.text.replace_range(start..self.cursor, text);
+        self.cursor = start + text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.set("");
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /* Deletes the char before the cursor */
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    /* Deletes the char under the cursor */
+    pub fn delete(&mut self) {
+        if self.cursor < self.text.len() {
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if let Some(c) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= c.len_utf8();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if let Some(c) = self.text[self.cursor..].chars().next() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /* Deletes the word before the cursor and any spaces after it, like
+     * readline's Ctrl-W */
+    pub fn delete_word(&mut self) {
+        let before = self.text[..self.cursor].trim_end_matches(' ');
+        let start = match before.rfind(' ') {
+            Some(i) => i + 1,
+            None => 0,
+        };
+
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn delete_to_start(&mut self) {
+        self.text.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    pub fn delete_to_end(&mut self) {
+        self.text.truncate(self.cursor);
+    }
+}
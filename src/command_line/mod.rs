@@ -18,122 +18,422 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 mod command;
+mod statusline;
+mod input;
+mod history;
+mod completion;
+mod tokenize;
+mod notation;
+
+#[cfg(test)]
+mod tests;
 
 use std::collections::HashMap;
 use std::sync::mpsc;
-use termion::{cursor, clear, event::Key};
+use termion::event::Key;
+use unicode_width::UnicodeWidthStr;
+use unicode_truncate::UnicodeTruncateStr;
 
 use crate::event::*;
 use crate::mode::Mode;
+use crate::GlobalState;
+use crate::render::{Style, Surface};
+use statusline::Statusline;
+use input::Input;
+use history::History;
+use completion::{Cycle, Sources};
+
+// How many aliases deep a command can go, so aliases that run each other
+// stop eventually
+const MAX_DEPTH: usize = 16;
+// Largest count that can be typed before a key
+const MAX_COUNT: usize = 9999;
 
 pub struct CommandLine {
-    contents: String,
-    statusline: String,
+    // Keys typed so far towards a keybind
+    pending: Vec<Key>,
+    // What is being typed in command or search mode
+    input: Input,
+    commands: History,
+    searches: History,
+    history_path: Option<String>,
+    completion: Option<Cycle>,
+    // Names of every component, for completion
+    components: Vec<String>,
+    statusline: Statusline,
+    status_text: (String, String),
     text: String,
     mode: Mode,
-    keybinds: HashMap<String, Event>,
+    // Keys and the commands they run
+    keybinds: HashMap<Vec<Key>, Vec<Vec<String>>>,
+    // Commands made with alias or define, by lowercase name
+    aliases: HashMap<String, Vec<Vec<String>>>,
+    // The last commands that changed something and their count, for
+    // repeatlast
+    last_change: Option<(Vec<Vec<String>>, Option<usize>)>,
     tx: mpsc::Sender<Event>,
 }
 
 impl CommandLine {
     pub fn new(tx: mpsc::Sender<Event>) -> CommandLine {
         CommandLine {
-            contents: String::new(),
-            statusline: String::new(),
+            pending: Vec::new(),
+            input: Input::new(),
+            commands: History::new(),
+            searches: History::new(),
+            history_path: None,
+            completion: None,
+            components: Vec::new(),
+            statusline: Statusline::new(""),
+            status_text: (String::new(), String::new()),
             text: String::new(),
             mode: Mode::TUI,
             keybinds: HashMap::new(),
+            aliases: HashMap::new(),
+            last_change: None,
             tx,
         }
     }
 
+    /* Reads history from path, which is also where it is saved to */
+    pub fn load_history(&mut self, path: &str) {
+        if let Ok((commands, searches)) = history::load(path) {
+            self.commands = commands;
+            self.searches = searches;
+        }
+
+        self.history_path = Some(path.to_string());
+    }
+
+    fn save_history(&mut self) {
+        if let Some(path) = &self.history_path {
+            if let Err(e) = history::save(path, &self.commands, &self.searches) {
+                self.put_text(format!("Could not save history: {}", e));
+            }
+        }
+    }
+
+    pub fn set_components(&mut self, mut names: Vec<String>) {
+        names.sort_unstable();
+        self.components = names;
+    }
+
     pub fn put_text(&mut self, text: String) {
         self.text = text;
     }
 
-    pub fn clear_text(&mut self) {
-        self.text = "".to_string();
+    pub fn set_statusline(&mut self, fmt: &str) {
+        self.statusline = Statusline::new(fmt);
     }
 
-    pub fn back(&mut self) -> Option<Event> {
-        if self.contents.is_empty() {
-            Some(spawn_mode_event(Mode::TUI))
-        } else {
-            self.contents.pop();
-            None
-        }
+    pub fn update_statusline(&mut self, state: &GlobalState, screen: &str) {
+        self.status_text = self.statusline.render(state, screen);
+    }
+
+    pub fn clear_text(&mut self) {
+        self.text = "".to_string();
     }
 
     pub fn mode(&mut self, m: Mode) {
         self.clear();
+        self.commands.reset();
+        self.searches.reset();
         self.mode = m;
     }
 
-
-    pub fn add(&mut self, c: char) {
+    pub fn add(&mut self, key: Key) {
         self.clear_text();
-        match self.mode {
-            Mode::TUI => {
-                if c != '\n' {
-                    self.contents.push(c);
-                }
+        self.pending.push(key);
 
-                if let Some(event) = self.keybinds.get(&self.contents) {
-                    self.tx.send(event.clone()).unwrap();
-                    self.clear();
-                } else if !self.keybinds.keys()
-                    .any(|s| s.starts_with(&self.contents))
-                {
-                    self.clear();
-                }
+        let (count, keys) = split_count(&self.pending, &self.keybinds);
+
+        match self.keybinds.get(keys).cloned() {
+            Some(commands) => {
+                self.run_bound(commands, count);
+                self.clear();
             },
-            _ => match c {
-                '\n' => self.run(),
-                _ => self.contents.push(c),
+            None if !self.keybinds.keys().any(|k| k.starts_with(keys)) => self.clear(),
+            None => (),
+        }
+    }
+
+    /* Runs the commands bound to a key, count times if there is a count */
+    fn run_bound(&mut self, commands: Vec<Vec<String>>, count: Option<usize>) {
+        let result = match count.map(|n| (n, command::with_count(&commands, n))) {
+            None => self.run_commands(&commands),
+            // Some commands take the count themselves, the rest are repeated
+            Some((_, Some(counted))) => self.run_commands(&counted),
+            Some((n, None)) => (0..n).try_fold(Vec::new(), |mut events, _| {
+                events.extend(self.run_commands(&commands)?);
+                Ok(events)
+            }),
+        };
+
+        match result {
+            Ok(events) => if events.iter().any(is_change) {
+                self.last_change = Some((commands, count));
             },
+            Err(()) => self.tx.send(spawn_invalid_event(&describe(&commands))).unwrap(),
+        }
+    }
+
+    /* Runs the last commands that changed something again. count replaces
+     * the count they were run with */
+    fn repeat_last(&mut self, count: Option<usize>) {
+        if let Some((commands, last_count)) = self.last_change.clone() {
+            self.run_bound(commands, count.or(last_count));
         }
     }
 
     pub fn clear(&mut self) {
-        self.contents = "".to_string();
+        self.pending.clear();
+        self.input.clear();
+        self.completion = None;
+    }
+
+    /* Completes what is before the cursor, or moves on to the next or
+     * previous completion if already completing */
+    fn complete(&mut self, state: &GlobalState, forward: bool) {
+        let cycle = match self.completion.take() {
+            Some(mut cycle) => {
+                cycle.step(forward);
+                cycle
+            },
+            None => {
+                let mut aliases: Vec<String> = self.aliases.keys().cloned().collect();
+                aliases.sort_unstable();
+
+                let sources = Sources {
+                    aliases: &aliases,
+                    components: &self.components,
+                    playlists: &state.playlists,
+                    library: &state.library,
+                };
+
+                let line = &self.input.text()[..self.input.cursor()];
+                let (start, candidates) = completion::complete(line, &sources);
+
+                if candidates.is_empty() {
+                    return;
+                }
+
+                Cycle {
+                    start,
+                    index: match forward {
+                        true => 0,
+                        false => candidates.len() - 1,
+                    },
+                    candidates,
+                }
+            },
+        };
+
+        self.input.replace(cycle.start, cycle.current());
+        self.completion = Some(cycle);
+    }
+
+    /* Replaces what is typed with the previous or next line in the history
+     * of the current mode */
+    fn history(&mut self, back: bool) {
+        let history = match self.mode {
+            Mode::Command => &mut self.commands,
+            Mode::Search => &mut self.searches,
+            Mode::TUI => return,
+        };
+
+        let entry = match back {
+            true => history.prev(self.input.text()),
+            false => history.next(),
+        };
+
+        if let Some(entry) = entry {
+            self.input.set(entry);
+        }
+    }
+
+    pub fn bind(&mut self, keys: Vec<Key>, commands: Vec<Vec<String>>) {
+        self.keybinds.insert(keys, commands);
+    }
+
+    /* Makes name a command that runs commands. Builtin commands can't be
+     * replaced */
+    pub fn alias(&mut self, name: &str, commands: Vec<Vec<String>>) -> Result<(), String> {
+        self.check_alias(name, &commands)?;
+        self.aliases.insert(name.to_lowercase(), commands);
+        Ok(())
     }
 
-    pub fn bind(&mut self, key: String, e: Event) {
-        self.keybinds.insert(key, e);
+    /* Whether name could be made an alias for commands */
+    fn check_alias(&self, name: &str, commands: &[Vec<String>]) -> Result<(), String> {
+        if command::NAMES.contains(&name.to_lowercase().as_str()) {
+            return Err(format!("Can't replace builtin command '{}'", name));
+        }
+
+        self.check(commands).map_err(|_| format!("Invalid alias '{}'", name))
+    }
+
+    /* Whether commands would run, going by the aliases there are now */
+    fn check(&self, commands: &[Vec<String>]) -> Result<(), ()> {
+        let valid = |cmd: &Vec<String>| match cmd.first() {
+            Some(name) if self.aliases.contains_key(&name.to_lowercase()) => true,
+            _ => command::parse(cmd).is_some(),
+        };
+
+        match !commands.is_empty() && commands.iter().all(valid) {
+            true => Ok(()),
+            false => Err(()),
+        }
+    }
+
+    /* Runs commands if every one of them is valid, so that a bad command
+     * doesn't leave the ones before it half done. Returns the events sent */
+    fn run_commands(&mut self, commands: &[Vec<String>]) -> Result<Vec<Event>, ()> {
+        let events = self.parse_commands(commands, 0)?;
+
+        for e in &events {
+            match e {
+                Event::ToCommandLine(CommandLineEvent::Alias(name, body)) =>
+                    self.alias(name, body.clone()).map_err(|_| ())?,
+                e => self.tx.send(e.clone()).unwrap(),
+            }
+        }
+
+        Ok(events)
+    }
+
+    /* The events commands make, expanding aliases. Fails at the first
+     * invalid command */
+    fn parse_commands(
+        &self,
+        commands: &[Vec<String>],
+        depth: usize,
+    ) -> Result<Vec<Event>, ()> {
+        if depth > MAX_DEPTH {
+            return Err(());
+        }
+
+        let mut ret = Vec::new();
+        let mut commands = commands.iter();
+
+        while let Some(cmd) = commands.next() {
+            let alias = cmd.first()
+                .and_then(|name| self.aliases.get(&name.to_lowercase()));
+
+            if let Some(alias) = alias {
+                ret.extend(self.parse_commands(alias, depth + 1)?);
+                continue;
+            }
+
+            // bind and alias take the rest of the line, so that they can
+            // run more than one command
+            let e = match command::parse(cmd).ok_or(())? {
+                Event::BindKey(key, mut body) => {
+                    body.extend(commands.by_ref().cloned());
+                    self.check(&body)?;
+
+                    Event::BindKey(key, body)
+                },
+                Event::ToCommandLine(CommandLineEvent::Alias(name, mut body)) => {
+                    body.extend(commands.by_ref().cloned());
+                    self.check_alias(&name, &body).map_err(|_| ())?;
+
+                    Event::ToCommandLine(CommandLineEvent::Alias(name, body))
+                },
+                e => e,
+            };
+
+            ret.push(e);
+        }
+
+        Ok(ret)
+    }
+
+    /* Runs sbrc, a line at a time. The lines between "define <name>" and
+     * "end" aren't run, but make up an alias called name */
+    pub fn source<I>(&mut self, lines: I)
+        where I: Iterator<Item = String>
+    {
+        // Line number, name and commands of the define being read, if any
+        let mut define: Option<(usize, String, Vec<Vec<String>>)> = None;
+
+        for (i, line) in lines.enumerate() {
+            let commands = match tokenize::tokenize(&line) {
+                Some(commands) => commands,
+                None => {
+                    self.tx.send(spawn_sbrc_error(i + 1, &line)).unwrap();
+                    continue;
+                },
+            };
+
+            let first = commands.first().map(|c| c.as_slice());
+
+            let result = match (&mut define, first) {
+                (None, Some([word, name])) if word == "define" && commands.len() == 1 => {
+                    define = Some((i + 1, name.to_string(), Vec::new()));
+                    Ok(())
+                },
+                (Some(_), Some([word])) if word == "end" && commands.len() == 1 => {
+                    let (start, name, body) = define.take().unwrap();
+
+                    if self.alias(&name, body).is_err() {
+                        self.tx.send(spawn_sbrc_error(start, &format!("define {}", name))).unwrap();
+                    }
+                    Ok(())
+                },
+                (Some((_, _, body)), _) => self.check(&commands)
+                    .map(|_| body.extend(commands)),
+                (None, _) => self.run_commands(&commands).map(|_| ()),
+            };
+
+            if result.is_err() {
+                self.tx.send(spawn_sbrc_error(i + 1, &line)).unwrap();
+            }
+        }
+
+        if let Some((start, name, _)) = define {
+            self.tx.send(spawn_sbrc_error(start, &format!("define {} has no end", name))).unwrap();
+        }
     }
 
     pub fn run(&mut self) {
+        let contents = self.input.text().to_string();
+
+        match self.mode {
+            Mode::Command => self.commands.push(&contents),
+            Mode::Search => self.searches.push(&contents),
+            Mode::TUI => (),
+        }
+
+        self.save_history();
+
         match self.mode {
             Mode::Command => {
-                let contents = self.contents.clone();
-                let args: Vec<&str> = contents.split(" ").collect();
-
-                let events = match command::parse(&args) {
-                    Some(e) => match e {
-                        Event::ToCommandLine(CommandLineEvent::Echo(_)) => vec![
-                            spawn_mode_event(Mode::TUI),
-                            e
-                        ],
-                        e => vec![
-                            spawn_mode_event(Mode::TUI),
-                            e.clone(),
-                            spawn_respond_event(&e),
-                        ],
-                    },
-                    None => vec![
-                        spawn_mode_event(Mode::TUI),
-                        spawn_invalid_event(&self.contents),
-                    ],
+                self.tx.send(spawn_mode_event(Mode::TUI)).unwrap();
+
+                let commands = tokenize::tokenize(&contents);
+                let result = match &commands {
+                    Some(commands) => self.run_commands(commands),
+                    None => Err(()),
                 };
 
-                for event in events {
-                    self.tx.send(event).unwrap();
-                }
+                match result {
+                    Ok(events) => {
+                        if events.iter().any(is_change) {
+                            self.last_change = commands.map(|c| (c, None));
+                        }
 
+                        // Echoing what ran would hide what echo says
+                        match events.last() {
+                            Some(Event::ToCommandLine(CommandLineEvent::Echo(_))) | None => (),
+                            Some(e) => self.tx.send(spawn_respond_event(e)).unwrap(),
+                        }
+                    },
+                    Err(()) => self.tx.send(spawn_invalid_event(&contents)).unwrap(),
+                }
             },
             Mode::Search => {
                 self.tx.send(
-                    Event::ToFocus(ComponentEvent::Search(self.contents.clone()))
+                    Event::ToFocus(ComponentEvent::Search(contents))
                 ).unwrap();
                 self.tx.send(Event::ToCommandLine(CommandLineEvent::Mode(Mode::TUI))).unwrap();
             },
@@ -142,8 +442,8 @@ impl CommandLine {
     }
 
 
-    pub fn draw(&self) {
-        let (w, h) = termion::terminal_size().unwrap();
+    pub fn draw(&self, surface: &mut Surface) {
+        let style = Style::default();
 
         let prefix = match self.mode {
             Mode::Command => ":",
@@ -151,37 +451,65 @@ impl CommandLine {
             _ => "",
         };
 
+        surface.clear();
+
         match self.mode {
-            Mode::Command | Mode::Search => print!(
-                "{}{}{}{}",
-               cursor::Goto(1, h),
-               clear::CurrentLine,
-               prefix,
-               self.contents
-            ),
-            Mode::TUI if self.text.is_empty() => print!(
-                "{}{}{}{}{}",
-                cursor::Goto(1, h),
-                clear::CurrentLine,
-                self.statusline,
-                cursor::Goto(w - self.contents.len() as u16, h),
-                self.contents,
-            ),
-            Mode::TUI => print!(
-                "{}{}{}{}{}",
-                cursor::Goto(1, h),
-                clear::CurrentLine,
-                self.text,
-                cursor::Goto(w - self.contents.len() as u16, h),
-                self.contents,
-            ),
+            Mode::Command | Mode::Search => {
+                let text = self.input.text();
+                let (before, after) = text.split_at(self.input.cursor());
+
+                // Scroll so the cursor stays on screen
+                let room = (surface.width() as usize).saturating_sub(prefix.width() + 1);
+                let before = before.unicode_truncate_start(room).0;
+
+                let mut x = surface.put(0, 0, prefix, style);
+                x += surface.put(x, 0, before, style);
+
+                let mut rest = after.chars();
+                let under = rest.next().map(|c| c.to_string()).unwrap_or(" ".to_string());
+
+                x += surface.put(x, 0, &under, Style { invert: true, ..style });
+                surface.put(x, 0, rest.as_str(), style);
+
+                if let Some(cycle) = &self.completion {
+                    let count = format!(" [{}/{}]", cycle.index + 1, cycle.candidates.len());
+                    let x = (surface.width() as usize).saturating_sub(count.width());
+
+                    surface.put(x as u16, 0, &count, style);
+                }
+            },
+            Mode::TUI if self.text.is_empty() => {
+                let (left, right) = &self.status_text;
+                let pending = notation::display(&self.pending);
+
+                let w = (surface.width() as usize).saturating_sub(pending.width() + 1);
+                let right = right.unicode_truncate_start(w).0;
+                let left = left.unicode_truncate(w - right.width()).0;
+
+                surface.put(0, 0, left, style);
+                surface.put((w - right.width()) as u16, 0, right, style);
+                surface.put(w as u16, 0, &pending, style);
+            },
+            Mode::TUI => {
+                let pending = notation::display(&self.pending);
+                let x = (surface.width() as usize).saturating_sub(pending.width() + 1);
+
+                surface.put(0, 0, &self.text, style);
+                surface.put(x as u16, 0, &pending, style);
+            },
         }
     }
 
-    pub fn handle(&mut self, e: &CommandLineEvent, tx: mpsc::Sender<Event>) {
+    pub fn handle(
+        &mut self,
+        e: &CommandLineEvent,
+        state: &GlobalState,
+        tx: mpsc::Sender<Event>,
+    ) {
         match e {
             CommandLineEvent::Echo(s) => self.put_text(s.to_string()),
             CommandLineEvent::Mode(m) => self.mode(*m),
+            CommandLineEvent::Statusline(fmt) => self.set_statusline(fmt),
             CommandLineEvent::SbrcError(line, msg) => self.put_text(
                 format!(
                     "sbrc: Invalid command at line {} '{}'",
@@ -189,46 +517,152 @@ impl CommandLine {
                     msg.to_string()
                 )
             ),
-            CommandLineEvent::Input(key) => match key {
-                Key::Char(':') => tx.send(
-                    Event::ToCommandLine(CommandLineEvent::Mode(Mode::Command))
-                ).unwrap(),
-                Key::Char('/') => tx.send(
-                    Event::ToCommandLine(CommandLineEvent::Mode(Mode::Search))
-                ).unwrap(),
-                Key::Esc => tx.send(
-                    Event::ToCommandLine(CommandLineEvent::Mode(Mode::TUI))
-                ).unwrap(),
-                Key::Backspace => if let Some(event) = self.back() {
-                    tx.send(event).unwrap();
-                },
-                Key::Char(c) => self.add(*c),
-                _ => (),
+            CommandLineEvent::Input(key) => match self.mode {
+                Mode::TUI => self.handle_key(key, &tx),
+                Mode::Command | Mode::Search => self.edit(key, state, &tx),
             },
             CommandLineEvent::SbrcNotFound => self.put_text(
                 "Sbrc not found. :q to quit.".to_string()
             ),
+            CommandLineEvent::RepeatLast(count) => self.repeat_last(*count),
+            CommandLineEvent::Alias(name, commands) =>
+                if let Err(msg) = self.alias(name, commands.clone()) {
+                    self.put_text(msg);
+                },
         }
     }
+
+    /* Keys pressed outside of command or search mode */
+    fn handle_key(&mut self, key: &Key, tx: &mpsc::Sender<Event>) {
+        match key {
+            Key::Char(':') => tx.send(
+                Event::ToCommandLine(CommandLineEvent::Mode(Mode::Command))
+            ).unwrap(),
+            Key::Char('/') => tx.send(
+                Event::ToCommandLine(CommandLineEvent::Mode(Mode::Search))
+            ).unwrap(),
+            // Escape and backspace take back keys typed towards a bind
+            Key::Esc if !self.pending.is_empty() => self.clear(),
+            Key::Backspace if !self.pending.is_empty() => {
+                self.pending.pop();
+            },
+            Key::Esc if !self.keybinds.contains_key([Key::Esc].as_slice()) => {
+                // Like vim, escape also leaves visual mode
+                tx.send(
+                    Event::ToFocus(ComponentEvent::ClearMarks)
+                ).unwrap();
+                self.clear();
+            },
+            key => self.add(*key),
+        }
+    }
+
+    /* Keys pressed while typing a command or search */
+    fn edit(&mut self, key: &Key, state: &GlobalState, tx: &mpsc::Sender<Event>) {
+        match key {
+            Key::Char('\t') | Key::BackTab => (),
+            _ => self.completion = None,
+        }
+
+        match key {
+            Key::Char('\t') => if let Mode::Command = self.mode {
+                self.complete(state, true)
+            },
+            Key::BackTab => if let Mode::Command = self.mode {
+                self.complete(state, false)
+            },
+            Key::Esc => tx.send(spawn_mode_event(Mode::TUI)).unwrap(),
+            Key::Backspace if self.input.is_empty() =>
+                tx.send(spawn_mode_event(Mode::TUI)).unwrap(),
+            Key::Backspace => self.input.backspace(),
+            Key::Delete => self.input.delete(),
+            Key::Left | Key::Ctrl('b') => self.input.left(),
+            Key::Right | Key::Ctrl('f') => self.input.right(),
+            Key::Home | Key::Ctrl('a') => self.input.home(),
+            Key::End | Key::Ctrl('e') => self.input.end(),
+            Key::Ctrl('w') => self.input.delete_word(),
+            Key::Ctrl('u') => self.input.delete_to_start(),
+            Key::Ctrl('k') => self.input.delete_to_end(),
+            Key::Up | Key::Ctrl('p') => self.history(true),
+            Key::Down | Key::Ctrl('n') => self.history(false),
+            Key::Char('\n') => self.run(),
+            Key::Char(c) => self.input.insert(*c),
+            _ => (),
+        }
+    }
+}
+
+/* Splits the count off the front of keys. Counts can't start with 0, and
+ * a digit that starts a bind is that bind, unless a count was already
+ * started by an unbound digit */
+fn split_count<'a>(
+    keys: &'a [Key],
+    keybinds: &HashMap<Vec<Key>, Vec<Vec<String>>>,
+) -> (Option<usize>, &'a [Key]) {
+    if let Some(first) = keys.first() {
+        if keybinds.keys().any(|k| k.first() == Some(first)) {
+            return (None, keys);
+        }
+    }
+
+    let digits: String = keys.iter()
+        .map_while(|key| match key {
+            Key::Char(c) if c.is_ascii_digit() => Some(*c),
+            _ => None,
+        })
+        .collect();
+
+    match digits.is_empty() || digits.starts_with('0') {
+        true => (None, keys),
+        false => (
+            Some(digits.parse().unwrap_or(MAX_COUNT).min(MAX_COUNT)),
+            &keys[digits.len()..],
+        ),
+    }
 }
 
-pub fn run_headless(cmd: &str, tx: mpsc::Sender<Event>) -> Result<(), ()> {
-    let cmd = cmd.split(" ").collect();
-    match command::parse(&cmd) {
-        Some(event) => {
-            tx.send(event).unwrap();
-            Ok(())
-        },
-        None => Err(())
+/* Whether e changes something, rather than moving around, marking or
+ * looking. Only changes are repeated by repeatlast */
+fn is_change(e: &Event) -> bool {
+    match e {
+        Event::ToFocus(e)
+        | Event::ToComponent(_, e)
+        | Event::ToAllComponents(e) => !matches!(e,
+            ComponentEvent::Next
+            | ComponentEvent::Prev
+            | ComponentEvent::GoTo(_)
+            | ComponentEvent::GoToTop
+            | ComponentEvent::GoToBottom
+            | ComponentEvent::GoToPlaying
+            | ComponentEvent::Search(_)
+            | ComponentEvent::Visual
+            | ComponentEvent::Mark
+            | ComponentEvent::ClearMarks
+            | ComponentEvent::Draw(..)
+        ),
+        Event::ToMpd(_) => true,
+        _ => false,
     }
 }
 
+/* Commands as they would be typed */
+fn describe(commands: &[Vec<String>]) -> String {
+    commands.iter()
+        .map(|cmd| cmd.join(" "))
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
 fn spawn_invalid_event(cmd: &str) -> Event {
     Event::ToCommandLine(CommandLineEvent::Echo(
         format!("Invalid Command '{}'", cmd)
     ))
 }
 
+fn spawn_sbrc_error(line: usize, text: &str) -> Event {
+    Event::ToCommandLine(CommandLineEvent::SbrcError(line, text.to_string()))
+}
+
 fn spawn_respond_event(e: &Event) -> Event {
     Event::ToCommandLine(CommandLineEvent::Echo(format!("Ran: {:?}", e)))
 }
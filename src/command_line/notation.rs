@@ -0,0 +1,157 @@
+/* Vim-like notation for keys, such as <C-d> or <pagedown>, for bind
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use termion::event::Key;
+
+/* Parses a sequence of keys. Most characters are just that key, and special
+ * keys go in angle brackets, ignoring case, like <up>, <f5>, <C-d> or
+ * <A-x>. A < that doesn't start one of these is just a <. Returns None for
+ * an empty sequence */
+pub fn parse(s: &str) -> Option<Vec<Key>> {
+    let mut ret = Vec::new();
+    let mut rest = s;
+
+    while let Some(c) = rest.chars().next() {
+        let special = match c {
+            '<' => rest.find('>').and_then(|end| Some((special(&rest[1..end])?, end + 1))),
+            _ => None,
+        };
+
+        match special {
+            Some((key, len)) => {
+                ret.push(key);
+                rest = &rest[len..];
+            },
+            None => {
+                ret.push(Key::Char(c));
+                rest = &rest[c.len_utf8()..];
+            },
+        }
+    }
+
+    match ret.is_empty() {
+        true => None,
+        false => Some(ret),
+    }
+}
+
+/* The key named by what is between the angle brackets */
+fn special(name: &str) -> Option<Key> {
+    let lower = name.to_lowercase();
+
+    let key = match lower.as_str() {
+        "space" => Key::Char(' '),
+        "tab" => Key::Char('\t'),
+        "s-tab" | "backtab" => Key::BackTab,
+        "cr" | "return" | "enter" => Key::Char('\n'),
+        "esc" => Key::Esc,
+        "bs" | "backspace" => Key::Backspace,
+        "del" | "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "lt" => Key::Char('<'),
+        "nul" => Key::Null,
+        _ => return function(&lower).or_else(|| modified(name)),
+    };
+
+    Some(key)
+}
+
+/* F1 to F12 */
+fn function(name: &str) -> Option<Key> {
+    match name.strip_prefix('f')?.parse::<u8>().ok()? {
+        n @ 1..=12 => Some(Key::F(n)),
+        _ => None,
+    }
+}
+
+/* A character with Ctrl or Alt held, as C-x, A-x or M-x. Ctrl takes either
+ * case, but Alt keeps it, since the terminal sends Alt with shift as Alt of
+ * the capital */
+fn modified(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    let modifier = chars.next()?.to_ascii_lowercase();
+
+    if chars.next()? != '-' {
+        return None;
+    }
+
+    let rest = chars.as_str();
+    let c = match rest.to_lowercase().as_str() {
+        "space" => ' ',
+        _ => {
+            let mut chars = rest.chars();
+            let c = chars.next()?;
+
+            match chars.next() {
+                Some(_) => return None,
+                None => c,
+            }
+        },
+    };
+
+    match (modifier, c.to_ascii_lowercase()) {
+        // The terminal sends these the same as other keys
+        ('c', 'i') => Some(Key::Char('\t')),
+        ('c', 'j') | ('c', 'm') => Some(Key::Char('\n')),
+        ('c', '[') => Some(Key::Esc),
+        ('c', ' ') | ('c', '@') => Some(Key::Null),
+        ('c', c) => Some(Key::Ctrl(c)),
+        ('a', _) | ('m', _) => Some(Key::Alt(c)),
+        _ => None,
+    }
+}
+
+/* keys written the way parse reads them */
+pub fn display(keys: &[Key]) -> String {
+    keys.iter().map(|key| match key {
+        Key::Char(' ') => "<space>".to_string(),
+        Key::Char('\t') => "<tab>".to_string(),
+        Key::Char('\n') => "<cr>".to_string(),
+        Key::Char('<') => "<lt>".to_string(),
+        Key::Char(c) => c.to_string(),
+        Key::BackTab => "<s-tab>".to_string(),
+        Key::Esc => "<esc>".to_string(),
+        Key::Backspace => "<bs>".to_string(),
+        Key::Delete => "<del>".to_string(),
+        Key::Insert => "<insert>".to_string(),
+        Key::Home => "<home>".to_string(),
+        Key::End => "<end>".to_string(),
+        Key::Up => "<up>".to_string(),
+        Key::Down => "<down>".to_string(),
+        Key::Left => "<left>".to_string(),
+        Key::Right => "<right>".to_string(),
+        Key::PageUp => "<pageup>".to_string(),
+        Key::PageDown => "<pagedown>".to_string(),
+        Key::F(n) => format!("<f{}>", n),
+        Key::Ctrl(' ') => "<C-space>".to_string(),
+        Key::Ctrl(c) => format!("<C-{}>", c),
+        Key::Alt(' ') => "<A-space>".to_string(),
+        Key::Alt(c) => format!("<A-{}>", c),
+        Key::Null => "<nul>".to_string(),
+        _ => String::new(),
+    }).collect()
+}
@@ -0,0 +1,90 @@
+/* Contains functionality for rendering the statusline
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use mpd::State;
+
+use crate::GlobalState;
+use crate::format;
+
+const RIGHT: &str = "{right}";
+
+/* Format for the statusline. Everything after {right} is right aligned */
+pub struct Statusline {
+    left: String,
+    right: String,
+}
+
+impl Statusline {
+    pub fn new(fmt: &str) -> Statusline {
+        match fmt.find(RIGHT) {
+            Some(i) => Statusline {
+                left: fmt[..i].to_string(),
+                right: fmt[i + RIGHT.len()..].to_string(),
+            },
+            None => Statusline {
+                left: fmt.to_string(),
+                right: String::new(),
+            },
+        }
+    }
+
+    pub fn render(&self, state: &GlobalState, screen: &str) -> (String, String) {
+        let lookup = |key: &str| placeholder(key, state, screen);
+
+        (
+            format::expand(&self.left, lookup),
+            format::expand(&self.right, lookup),
+        )
+    }
+}
+
+fn placeholder(key: &str, state: &GlobalState, screen: &str) -> Option<String> {
+    let song = state.now_playing.as_ref();
+    let status = state.status.as_ref();
+
+    match key {
+        "elapsed" => state.elapsed().map(format::duration),
+        "duration" => state.duration().map(format::duration),
+        "volume" => match status?.volume {
+            vol if vol < 0 => None,
+            vol => Some(vol.to_string()),
+        },
+        "state" => Some(match status?.state {
+            State::Play => "playing",
+            State::Pause => "paused",
+            State::Stop => "stopped",
+        }.to_string()),
+        "flags" => status.map(|status| {
+            [
+                (status.repeat, 'r'),
+                (status.random, 'z'),
+                (status.single, 's'),
+                (status.consume, 'c'),
+            ].iter()
+                .map(|(on, c)| if *on { *c } else { '-' })
+                .collect()
+        }),
+        "queue_len" => status.map(|status| status.queue_len.to_string()),
+        "connection" => Some(state.connection.to_string()),
+        "loading" => state.loading
+            .map(|total| format!("{}/{}", state.library.len(), total)),
+        "screen" => Some(screen.to_string()),
+        key => format::song_placeholder(key, song?),
+    }
+}
@@ -0,0 +1,603 @@
+/* Tests for the commandline
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+use std::fs;
+use std::sync::Arc;
+
+use mpd::Song;
+
+use crate::render::Grid;
+use crate::library::Library;
+
+fn typed(text: &str) -> Input {
+    let mut input = Input::new();
+    text.chars().for_each(|c| input.insert(c));
+    input
+}
+
+/* Sends each key to command_line, passing back any mode changes or repeats
+ * it asks for. Returns every other event it sends */
+fn press(
+    command_line: &mut CommandLine,
+    rx: &mpsc::Receiver<Event>,
+    keys: &[Key],
+) -> Vec<Event> {
+    press_with(command_line, &GlobalState::new(), rx, keys)
+}
+
+fn press_with(
+    command_line: &mut CommandLine,
+    state: &GlobalState,
+    rx: &mpsc::Receiver<Event>,
+    keys: &[Key],
+) -> Vec<Event> {
+    let (tx, own_rx) = mpsc::channel();
+    let mut ret = Vec::new();
+
+    for key in keys {
+        command_line.handle(&CommandLineEvent::Input(*key), state, tx.clone());
+
+        for e in own_rx.try_iter().chain(rx.try_iter()) {
+            match e {
+                Event::ToCommandLine(e @ CommandLineEvent::Mode(_))
+                | Event::ToCommandLine(e @ CommandLineEvent::RepeatLast(_)) =>
+                    command_line.handle(&e, state, tx.clone()),
+                e => ret.push(e),
+            }
+        }
+    }
+
+    ret
+}
+
+fn keys(s: &str) -> Vec<Key> {
+    s.chars().map(Key::Char).collect()
+}
+
+fn line(command_line: &CommandLine) -> String {
+    line_in(command_line, 12)
+}
+
+fn line_in(command_line: &CommandLine, w: u16) -> String {
+    let mut grid = Grid::new(w, 1);
+    command_line.draw(&mut grid.surface());
+    grid.lines().remove(0)
+}
+
+#[test]
+fn test_input_cursor_movement() -> Result<(), String> {
+    let mut input = typed("héllo");
+
+    input.left();
+    input.left();
+    input.insert('X');
+    assert_eq!(input.text(), "hélXlo");
+
+    input.home();
+    input.right();
+    input.right();
+    input.backspace();
+    assert_eq!(input.text(), "hlXlo");
+
+    input.delete();
+    assert_eq!(input.text(), "hXlo");
+
+    input.end();
+    input.right();
+    input.insert('!');
+    assert_eq!(input.text(), "hXlo!");
+
+    Ok(())
+}
+
+#[test]
+fn test_input_deletes() -> Result<(), String> {
+    let mut input = typed("goto  top now");
+
+    input.left();
+    input.left();
+    input.left();
+    input.left();
+    input.delete_word();
+    assert_eq!(input.text(), "goto   now");
+    assert_eq!(input.cursor(), 6);
+
+    input.delete_word();
+    assert_eq!(input.text(), " now");
+
+    input.delete_to_end();
+    assert_eq!(input.text(), "");
+
+    let mut input = typed("seek 30");
+    input.left();
+    input.delete_to_start();
+    assert_eq!(input.text(), "0");
+    assert_eq!(input.cursor(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_history_navigation() -> Result<(), String> {
+    let mut history = History::new();
+
+    assert_eq!(history.prev("typing"), None);
+
+    history.push("play");
+    history.push("stop");
+    history.push("play");
+    history.push("");
+
+    assert_eq!(history.entries(), &["stop", "play"]);
+
+    assert_eq!(history.prev("typing"), Some("play"));
+    assert_eq!(history.prev("ignored"), Some("stop"));
+    assert_eq!(history.prev("ignored"), Some("stop"));
+    assert_eq!(history.next(), Some("play"));
+    assert_eq!(history.next(), Some("typing"));
+    assert_eq!(history.next(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_history_file() -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("sbhistory-{}", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    let mut commands = History::new();
+    commands.push("volume +5");
+    commands.push("q");
+
+    let mut searches = History::new();
+    searches.push("beatles");
+
+    history::save(path, &commands, &searches).map_err(|e| e.to_string())?;
+    let loaded = history::load(path).map_err(|e| e.to_string());
+    fs::remove_file(path).map_err(|e| e.to_string())?;
+
+    assert_eq!(loaded?, (commands, searches));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_line_editing() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    press(&mut command_line, &rx, &keys(":volume 5"));
+    press(&mut command_line, &rx, &[Key::Left, Key::Char('+')]);
+    assert_eq!(line(&command_line), ":volume +5  ");
+
+    press(&mut command_line, &rx, &[Key::Home, Key::Ctrl('k')]);
+    press(&mut command_line, &rx, &keys("echo hi"));
+
+    assert!(press(&mut command_line, &rx, &[Key::Char('\n')]).iter().any(|e| matches!(e,
+        Event::ToCommandLine(CommandLineEvent::Echo(s)) if s == "hi")));
+
+    // Command and search history are kept apart
+    press(&mut command_line, &rx, &keys("/abba\n"));
+    press(&mut command_line, &rx, &[Key::Char(':'), Key::Up]);
+    assert_eq!(line(&command_line), ":echo hi    ");
+
+    press(&mut command_line, &rx, &[Key::Esc, Key::Char('/'), Key::Up]);
+    assert_eq!(line(&command_line), "/abba       ");
+
+    press(&mut command_line, &rx, &[Key::Down, Key::Backspace]);
+    assert_eq!(line(&command_line), "            ");
+
+    Ok(())
+}
+
+fn candidates(line: &str, state: &GlobalState) -> Vec<String> {
+    let components = vec!["library".to_string(), "queue".to_string()];
+    let aliases = vec!["party".to_string()];
+    let sources = completion::Sources {
+        aliases: &aliases,
+        components: &components,
+        playlists: &state.playlists,
+        library: &state.library,
+    };
+
+    completion::complete(line, &sources).1
+}
+
+fn completion_state() -> GlobalState {
+    let mut song = Song::default();
+    song.file = "abba/waterloo.flac".to_string();
+    song.tags.insert("Artist".to_string(), "ABBA".to_string());
+    song.tags.insert("Album".to_string(), "Waterloo".to_string());
+    song.tags.insert("Genre".to_string(), "Pop".to_string());
+
+    let mut library = Library::new();
+    library.add(vec![song]);
+
+    let mut state = GlobalState::new();
+    state.library = Arc::new(library);
+    state.playlists = vec!["Road Trip".to_string(), "Rainy Day".to_string()];
+    state
+}
+
+#[test]
+fn test_completion_sources() -> Result<(), String> {
+    let state = completion_state();
+
+    assert_eq!(candidates("volu", &state), vec!["volume"]);
+    assert_eq!(candidates("switchscreen q", &state), vec!["queue"]);
+    assert_eq!(candidates("move d", &state), vec!["down"]);
+    assert_eq!(candidates("set mpd_p", &state), vec!["mpd_password", "mpd_port"]);
+    assert_eq!(candidates("rmplaylist road t", &state), vec!["Road Trip"]);
+    assert_eq!(candidates("search wat", &state), vec!["Waterloo"]);
+    assert_eq!(candidates("bind x switchscreen l", &state), vec!["library"]);
+    assert!(candidates("volume 5", &state).is_empty());
+    assert_eq!(candidates("par", &state), vec!["party"]);
+    assert_eq!(candidates("clear; alias x screen q", &state), vec!["queue"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_completion_cycling() -> Result<(), String> {
+    let state = completion_state();
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+    command_line.set_components(vec!["queue".to_string(), "library".to_string()]);
+
+    press_with(&mut command_line, &state, &rx, &keys(":screen "));
+    press_with(&mut command_line, &state, &rx, &[Key::Char('\t')]);
+    assert_eq!(line_in(&command_line, 21), ":screen library [1/2]");
+
+    press_with(&mut command_line, &state, &rx, &[Key::Char('\t')]);
+    assert_eq!(line_in(&command_line, 21), ":screen queue   [2/2]");
+
+    press_with(&mut command_line, &state, &rx, &[Key::BackTab, Key::BackTab]);
+    assert_eq!(line_in(&command_line, 21), ":screen queue   [2/2]");
+
+    // Anything else accepts the completion
+    press_with(&mut command_line, &state, &rx, &[Key::End]);
+    assert_eq!(line_in(&command_line, 21), ":screen queue        ");
+
+    Ok(())
+}
+
+fn tokens(line: &str) -> Option<Vec<Vec<String>>> {
+    tokenize::tokenize(line)
+}
+
+fn commands(v: &[&[&str]]) -> Option<Vec<Vec<String>>> {
+    Some(v.iter().map(|c| c.iter().map(|s| s.to_string()).collect()).collect())
+}
+
+#[test]
+fn test_tokenize() -> Result<(), String> {
+    assert_eq!(tokens("  echo  hello world "), commands(&[&["echo", "hello", "world"]]));
+    assert_eq!(tokens(r#"save "Road Trip""#), commands(&[&["save", "Road Trip"]]));
+    assert_eq!(tokens(r#"echo 'a "b"' "c 'd'""#), commands(&[&["echo", r#"a "b""#, "c 'd'"]]));
+    assert_eq!(tokens(r#"echo a\ b "\"" '\'"#), commands(&[&["echo", "a b", "\"", "\\"]]));
+    assert_eq!(tokens(r#"echo "" x"y"z"#), commands(&[&["echo", "", "xyz"]]));
+    assert_eq!(tokens("bind <space> select"), commands(&[&["bind", " ", "select"]]));
+    assert_eq!(tokens("echo '<space>'"), commands(&[&["echo", "<space>"]]));
+    assert_eq!(tokens("bind # select"), commands(&[&["bind"]]));
+    assert_eq!(tokens("echo a#b '#' # comment"), commands(&[&["echo", "a#b", "#"]]));
+    assert_eq!(tokens("   # comment"), commands(&[]));
+    assert_eq!(tokens("echo 'oops"), None);
+    assert_eq!(tokens("echo oops\\"), None);
+
+    // ; splits commands, unless quoted or escaped
+    assert_eq!(tokens("clear;select ; play;"), commands(&[&["clear"], &["select"], &["play"]]));
+    assert_eq!(tokens(r"echo 'a;b' c\;d ;; "), commands(&[&["echo", "a;b", "c;d"]]));
+
+    Ok(())
+}
+
+/* Runs each line as sbrc and makes any binds, returning the events sent */
+fn source(command_line: &mut CommandLine, rx: &mpsc::Receiver<Event>, sbrc: &str) -> Vec<Event> {
+    command_line.source(sbrc.lines().map(|s| s.to_string()));
+
+    let events: Vec<Event> = rx.try_iter().collect();
+
+    for e in &events {
+        if let Event::BindKey(key, commands) = e {
+            command_line.bind(key.clone(), commands.clone());
+        }
+    }
+
+    events
+}
+
+fn echoes(events: &[Event]) -> Vec<String> {
+    events.iter()
+        .filter_map(|e| match e {
+            Event::ToCommandLine(CommandLineEvent::Echo(s)) => Some(s.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn sbrc_errors(events: &[Event]) -> Vec<usize> {
+    events.iter()
+        .filter_map(|e| match e {
+            Event::ToCommandLine(CommandLineEvent::SbrcError(line, _)) => Some(*line),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_source() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    let events = source(&mut command_line, &rx, "echo hello   world\n\
+        \n\
+        # switchscreen queue\n\
+        set statusline '{title}  {right}{elapsed}'\n\
+        echo \"unclosed\n\
+        nonsense");
+
+    assert_eq!(echoes(&events), vec!["hello world"]);
+    assert!(events.iter().any(|e| matches!(e,
+        Event::ToCommandLine(CommandLineEvent::Statusline(s)) if s == "{title}  {right}{elapsed}")));
+
+    // Blank lines and comments are fine, but do nothing
+    assert_eq!(sbrc_errors(&events), vec![5, 6]);
+
+    Ok(())
+}
+
+#[test]
+fn test_chaining_and_aliases() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    let events = source(&mut command_line, &rx, "echo one; echo two\n\
+        alias greet echo hi; echo there\n\
+        greet; echo after\n\
+        define party\n\
+        \x20   clear # comments are fine here too\n\
+        \x20   greet\n\
+        end\n\
+        bind P party; play\n\
+        alias pause echo no\n\
+        alias loop loop\n\
+        echo bad; nonsense; echo unreached\n\
+        define broken\n\
+        nonsense\n\
+        end\n\
+        bind x nonsense; play");
+
+    // Nothing on a line with an invalid command runs
+    assert_eq!(echoes(&events), vec!["one", "two", "hi", "there", "after"]);
+
+    // Builtins can't be replaced, and aliases can only use what exists. A define
+    // left with nothing in it is reported where it starts
+    assert_eq!(sbrc_errors(&events), vec![9, 10, 11, 13, 12, 15]);
+
+    let binds: Vec<&Event> = events.iter()
+        .filter(|e| matches!(e, Event::BindKey(..)))
+        .collect();
+
+    match binds.as_slice() {
+        [Event::BindKey(key, cmds)] => {
+            assert_eq!(key, &vec![Key::Char('P')]);
+            assert_eq!(Some(cmds.clone()), commands(&[&["party"], &["play"]]));
+        },
+        _ => return Err(format!("Expected one bind, got {:?}", binds)),
+    }
+
+    // Binds run every command, going through aliases
+    let events = press(&mut command_line, &rx, &keys("P"));
+    assert!(matches!(events.as_slice(), [
+        Event::ToMpd(MpdEvent::ClearQueue),
+        Event::ToCommandLine(CommandLineEvent::Echo(hi)),
+        Event::ToCommandLine(CommandLineEvent::Echo(there)),
+        Event::ToMpd(MpdEvent::Play),
+    ] if hi == "hi" && there == "there"));
+
+    // Aliases that end up running themselves stop
+    let events = press(&mut command_line, &rx, &keys(":alias a echo hi\n:alias b a\n:alias a b\n:a\n"));
+    assert_eq!(echoes(&events).last().map(|s| s.as_str()), Some("Invalid Command 'a'"));
+
+    Ok(())
+}
+
+#[test]
+fn test_counts_and_repeat() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    source(&mut command_line, &rx, "bind j down\n\
+        bind G bot\n\
+        bind gg top\n\
+        bind dd delete\n\
+        bind 1 switchscreen queue\n\
+        bind . repeatlast");
+
+    let next = |events: &[Event]| events.iter()
+        .filter(|e| matches!(e, Event::ToFocus(ComponentEvent::Next)))
+        .count();
+    let deletes = |events: &[Event]| events.iter()
+        .filter_map(|e| match e {
+            Event::ToFocus(ComponentEvent::Delete(n)) => Some(*n),
+            _ => None,
+        })
+        .collect::<Vec<usize>>();
+
+    assert_eq!(next(&press(&mut command_line, &rx, &keys("5j"))), 5);
+    assert_eq!(next(&press(&mut command_line, &rx, &keys("23j"))), 23);
+
+    // Counts are given to top and bottom, counting from 1
+    assert!(matches!(press(&mut command_line, &rx, &keys("42G")).as_slice(),
+        [Event::ToFocus(ComponentEvent::GoTo(41))]));
+    assert!(matches!(press(&mut command_line, &rx, &keys("3gg")).as_slice(),
+        [Event::ToFocus(ComponentEvent::GoTo(2))]));
+
+    // A bound digit wins unless a count has started, and counts can't
+    // start with 0
+    assert!(matches!(press(&mut command_line, &rx, &keys("1")).as_slice(),
+        [Event::ToApp(AppEvent::SwitchScreen(s))] if s == "queue"));
+    assert_eq!(next(&press(&mut command_line, &rx, &keys("21j"))), 21);
+    assert_eq!(next(&press(&mut command_line, &rx, &keys("0j"))), 1);
+
+    // Delete takes the count itself, so it's one delete of that many tracks.
+    // Repeat does the last change again, not the motions since
+    assert_eq!(deletes(&press(&mut command_line, &rx, &keys("dd"))), vec![1]);
+    assert_eq!(deletes(&press(&mut command_line, &rx, &keys("2dd"))), vec![2]);
+    assert_eq!(deletes(&press(&mut command_line, &rx, &keys("j."))), vec![2]);
+    assert_eq!(deletes(&press(&mut command_line, &rx, &keys("3."))), vec![3]);
+    assert_eq!(deletes(&press(&mut command_line, &rx, &keys("."))), vec![3]);
+
+    // Changes from the commandline can be repeated too
+    press(&mut command_line, &rx, &keys(":volume +5\n"));
+    assert!(matches!(press(&mut command_line, &rx, &keys(".")).as_slice(),
+        [Event::ToMpd(MpdEvent::Volume(Adjustment::By(5)))]));
+
+    Ok(())
+}
+
+#[test]
+fn test_counts_with_shipped_binds() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    source(&mut command_line, &rx, include_str!("../../sbrc"));
+
+    // The screen keys are digits, so only the other digits start a count
+    assert!(matches!(press(&mut command_line, &rx, &keys("2")).as_slice(),
+        [Event::ToApp(AppEvent::SwitchScreen(s))] if s == "QueueScreen"));
+
+    let next = press(&mut command_line, &rx, &keys("8j"));
+    assert_eq!(next.len(), 8);
+    assert!(next.iter().all(|e| matches!(e, Event::ToFocus(ComponentEvent::Next))));
+
+    assert!(matches!(press(&mut command_line, &rx, &keys("72G")).as_slice(),
+        [Event::ToFocus(ComponentEvent::GoTo(71))]));
+    assert!(matches!(press(&mut command_line, &rx, &keys("93dd")).as_slice(),
+        [Event::ToFocus(ComponentEvent::Delete(93))]));
+
+    Ok(())
+}
+
+#[test]
+fn test_key_notation() -> Result<(), String> {
+    let parse = notation::parse;
+
+    assert_eq!(parse("gg"), Some(vec![Key::Char('g'), Key::Char('g')]));
+    assert_eq!(parse("<space>"), Some(vec![Key::Char(' ')]));
+    assert_eq!(parse("<Return>"), Some(vec![Key::Char('\n')]));
+    assert_eq!(parse("<C-d><c-U>"), Some(vec![Key::Ctrl('d'), Key::Ctrl('u')]));
+    assert_eq!(parse("<A-x><M-X>"), Some(vec![Key::Alt('x'), Key::Alt('X')]));
+    assert_eq!(parse("<PageDown><f5>"), Some(vec![Key::PageDown, Key::F(5)]));
+    assert_eq!(parse("<up><down><left><right>"),
+        Some(vec![Key::Up, Key::Down, Key::Left, Key::Right]));
+    assert_eq!(parse("<tab><s-tab><esc>"), Some(vec![Key::Char('\t'), Key::BackTab, Key::Esc]));
+    assert_eq!(parse("<C-i><C-m><C-space>"),
+        Some(vec![Key::Char('\t'), Key::Char('\n'), Key::Null]));
+
+    // Anything else in brackets is just those characters
+    assert_eq!(parse("<f13>"), Some("<f13>".chars().map(Key::Char).collect()));
+    assert_eq!(parse("<<lt>"), Some(vec![Key::Char('<'), Key::Char('<')]));
+    assert_eq!(parse(""), None);
+
+    let keys = vec![Key::Char(' '), Key::Ctrl('d'), Key::F(12), Key::Char('<'), Key::Char('x')];
+    assert_eq!(notation::display(&keys), "<space><C-d><f12><lt>x");
+    assert_eq!(parse(&notation::display(&keys)), Some(keys));
+
+    Ok(())
+}
+
+#[test]
+fn test_bind_special_keys() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut command_line = CommandLine::new(tx);
+
+    source(&mut command_line, &rx, "bind <return> play\n\
+        bind <C-d> down\n\
+        bind <pagedown> bot\n\
+        bind g<up> top\n\
+        bind <esc> stop");
+
+    assert!(matches!(press(&mut command_line, &rx, &[Key::Char('\n')]).as_slice(),
+        [Event::ToMpd(MpdEvent::Play)]));
+    assert_eq!(press(&mut command_line, &rx, &[Key::Char('3'), Key::Ctrl('d')]).len(), 3);
+    assert!(matches!(press(&mut command_line, &rx, &[Key::PageDown]).as_slice(),
+        [Event::ToFocus(ComponentEvent::GoToBottom)]));
+
+    // Keys typed towards a bind are shown until it is finished
+    press(&mut command_line, &rx, &[Key::Char('2'), Key::Char('g')]);
+    assert_eq!(line(&command_line), "         2g ");
+    assert!(matches!(press(&mut command_line, &rx, &[Key::Up]).as_slice(),
+        [Event::ToFocus(ComponentEvent::GoTo(1))]));
+
+    // Escape takes back typed keys before it runs its own bind
+    assert!(press(&mut command_line, &rx, &[Key::Char('g'), Key::Esc]).is_empty());
+    assert!(matches!(press(&mut command_line, &rx, &[Key::Esc]).as_slice(),
+        [Event::ToMpd(MpdEvent::Stop)]));
+
+    Ok(())
+}
+
+fn parsed(line: &str) -> Option<Event> {
+    let commands = tokenize::tokenize(line)?;
+    command::parse(commands.first()?)
+}
+
+fn seek(line: &str) -> Option<Adjustment> {
+    match parsed(line)? {
+        Event::ToMpd(MpdEvent::Seek(a)) => Some(a),
+        _ => None,
+    }
+}
+
+fn volume(line: &str) -> Option<Adjustment> {
+    match parsed(line)? {
+        Event::ToMpd(MpdEvent::Volume(a)) => Some(a),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_adjustments() -> Result<(), String> {
+    assert_eq!(volume("volume 50"), Some(Adjustment::To(50)));
+    assert_eq!(volume("vol +5"), Some(Adjustment::By(5)));
+    assert_eq!(volume("vol -5"), Some(Adjustment::By(-5)));
+
+    assert_eq!(seek("seek 90"), Some(Adjustment::To(90)));
+    assert_eq!(seek("seek 1:30"), Some(Adjustment::To(90)));
+    assert_eq!(seek("seek 1:02:03"), Some(Adjustment::To(3723)));
+    assert_eq!(seek("seek +0:10"), Some(Adjustment::By(10)));
+    assert_eq!(seek("seek -1:00"), Some(Adjustment::By(-60)));
+
+    assert!(matches!(parsed("crossfade 3"), Some(Event::ToMpd(MpdEvent::Crossfade(3)))));
+
+    // Anything that isn't a number is invalid
+    assert_eq!(volume("vol"), None);
+    assert_eq!(volume("vol loud"), None);
+    assert_eq!(volume("vol +"), None);
+    assert_eq!(volume("vol --5"), None);
+    assert_eq!(seek("seek 1:xx"), None);
+    assert_eq!(seek("seek 1::2"), None);
+    assert_eq!(seek("seek -"), None);
+    assert!(parsed("crossfade three").is_none());
+
+    Ok(())
+}
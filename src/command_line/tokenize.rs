@@ -0,0 +1,108 @@
+/* Splitting commands into arguments
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+#[derive(Debug)]
+#[derive(Copy)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+enum Quote {
+    Single,
+    Double,
+}
+
+/* Splits line into commands on ;, and each command into arguments on
+ * whitespace, like a shell would.
+ *
+ * Text in single quotes is taken as is. In double quotes, and outside of
+ * quotes, a backslash takes the next character as is. An unquoted # starting
+ * an argument comments out the rest of the line, and an unquoted <space> on
+ * its own is a single space. Returns None for an unclosed quote or a
+ * backslash at the very end */
+pub fn tokenize(line: &str) -> Option<Vec<Vec<String>>> {
+    let mut ret = Vec::new();
+    let mut command = Vec::new();
+    let mut token = String::new();
+    // Whether there is a token at all, since quotes can make an empty one
+    let mut in_token = false;
+    // Whether any of the token was quoted or escaped
+    let mut literal = false;
+    let mut quote: Option<Quote> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(Quote::Single), '\'') | (Some(Quote::Double), '"') => quote = None,
+            (Some(Quote::Single), c) => token.push(c),
+            (_, '\\') => {
+                token.push(chars.next()?);
+                in_token = true;
+                literal = true;
+            },
+            (Some(Quote::Double), c) => token.push(c),
+            (None, '\'') | (None, '"') => {
+                quote = match c {
+                    '\'' => Some(Quote::Single),
+                    _ => Some(Quote::Double),
+                };
+                in_token = true;
+                literal = true;
+            },
+            (None, '#') if !in_token => break,
+            (None, c) if c.is_whitespace() || c == ';' => {
+                if in_token {
+                    command.push(finish(&mut token, literal));
+                    in_token = false;
+                    literal = false;
+                }
+
+                if c == ';' && !command.is_empty() {
+                    ret.push(std::mem::take(&mut command));
+                }
+            },
+            (None, c) => {
+                token.push(c);
+                in_token = true;
+            },
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+
+    if in_token {
+        command.push(finish(&mut token, literal));
+    }
+
+    if !command.is_empty() {
+        ret.push(command);
+    }
+
+    Some(ret)
+}
+
+/* Takes the finished token, turning an unquoted <space> into a space */
+fn finish(token: &mut String, literal: bool) -> String {
+    let token = std::mem::take(token);
+
+    match (literal, token.as_str()) {
+        (false, "<space>") => " ".to_string(),
+        _ => token,
+    }
+}
@@ -18,6 +18,7 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use crate::components::{Component, Components};
+use crate::render::Surface;
 
 #[derive(Debug)]
 #[derive(PartialEq)]
@@ -41,7 +42,7 @@ impl EmptySpace {
 impl Component for EmptySpace {
     fn name(&self) -> &str { &self.name }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, _focus: bool) {
-        self.clear(x, y, w, h);
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        surface.clear();
     }
 }
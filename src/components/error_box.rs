@@ -17,9 +17,9 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
-use termion::color;
-use termion::cursor;
+use crate::color::Color;
 use crate::components::{Component, Components};
+use crate::render::{Style, Surface};
 
 #[derive(Debug)]
 #[derive(PartialEq)]
@@ -38,20 +38,10 @@ impl ErrorBox {
 impl Component for ErrorBox {
     fn name(&self) -> &str { "Error" }
 
-    fn draw(
-        &self,
-        x: u16, y: u16, w: u16, h: u16,
-        _focus: bool,
-    ) {
-        let mut text = "Error".to_string();
-        text.truncate((w - 2).into());
-
-        print!("{}", color::Fg(color::Red));
-        self.border(x, y, w, h);
-        print!("{}{}{}",
-            cursor::Goto(x + 1, y + 1),
-            text,
-            color::Fg(color::Reset),
-        )
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        let w = surface.width().saturating_sub(2);
+
+        surface.border(Style::new(Color::Red));
+        surface.area(1, 1, w, 1).put(0, 0, "Error", Style::new(Color::Red));
     }
 }
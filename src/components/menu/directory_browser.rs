@@ -0,0 +1,264 @@
+/* TUI Component for browsing mpd's music directory
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::sync::mpsc;
+
+use crate::event::*;
+use crate::render::Surface;
+use crate::GlobalState;
+use crate::music::DirEntry;
+use crate::library::{Library, SongId};
+use crate::components::{Component, Components, menu::{Menu, MenuStyle}};
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct DirectoryBrowser {
+    menu: Menu,
+    // Directory being shown. Empty is the root of the music directory
+    path: String,
+    // Contents of path. Unless path is the root, the first entry is its
+    // parent, shown as ..
+    entries: Vec<DirEntry>,
+    // Directory just left, so the cursor can go back to it
+    came_from: Option<String>,
+}
+
+impl DirectoryBrowser {
+    pub fn enumed(
+        name: &str,
+        style: MenuStyle,
+    ) -> Components {
+        Components::DirectoryBrowser(
+            DirectoryBrowser::new(
+                name,
+                style,
+            )
+        )
+    }
+
+    pub fn new(
+        name: &str,
+        style: MenuStyle,
+    ) -> DirectoryBrowser {
+        DirectoryBrowser {
+            path: String::new(),
+            entries: Vec::new(),
+            came_from: None,
+            menu: Menu::new(name, style, None),
+        }
+    }
+
+    fn spawn_list_event(&self, path: &str) -> Event {
+        Event::ToMpd(MpdEvent::ListDirectory(
+            self.name().to_string(),
+            path.to_string(),
+        ))
+    }
+
+    fn spawn_update_event(&self, library: &Library) -> Event {
+        let tracks = match self.entries.get(self.menu.selection) {
+            Some(entry) => tracks_in(entry, library),
+            None => Vec::new(),
+        };
+
+        Event::ToAllComponents(ComponentEvent::DirectoryBrowserUpdated(
+            self.name().to_string(),
+            tracks,
+        ))
+    }
+
+    fn is_up(&self, i: usize) -> bool {
+        !self.path.is_empty() && i == 0
+    }
+
+    /* Shows entries, the contents of path. If path is already shown, the
+     * cursor and marks stay where they were */
+    fn set_entries(&mut self, path: &str, entries: &[DirEntry]) {
+        let refresh = path == self.path;
+
+        self.path = path.to_string();
+        self.entries = Vec::new();
+
+        if !path.is_empty() {
+            self.entries.push(DirEntry::Directory(parent(path).to_string()));
+        }
+
+        self.entries.extend(entries.iter().cloned());
+
+        let items = self.entries.iter().enumerate()
+            .map(|(i, entry)| match entry {
+                _ if self.is_up(i) => "..".to_string(),
+                DirEntry::Directory(path) => format!("{}/", basename(path)),
+                DirEntry::File(path) => basename(path).to_string(),
+            }).collect();
+
+        if refresh {
+            self.menu.replace_items(items);
+            return;
+        }
+
+        self.menu.clear_marks();
+        self.menu.items = items;
+        self.menu.selection = match self.came_from.take() {
+            Some(dir) => self.entries.iter()
+                .position(|e| *e == DirEntry::Directory(dir.clone()))
+                .filter(|i| !self.is_up(*i))
+                .unwrap_or(0),
+            None => 0,
+        };
+    }
+
+    /* Paths of the marked entries, or the entry under the cursor. .. is
+     * left out */
+    fn marked_paths(&self) -> Vec<String> {
+        self.menu.marked().iter()
+            .filter(|i| !self.is_up(**i))
+            .filter_map(|i| self.entries.get(*i))
+            .map(|e| e.path().to_string())
+            .collect()
+    }
+}
+
+impl Component for DirectoryBrowser {
+    fn name(&self) -> &str { &self.menu.name }
+
+    fn handle(
+        &mut self,
+        state: &GlobalState,
+        e: &ComponentEvent,
+        tx: mpsc::Sender<Event>
+    ) {
+        match e {
+            ComponentEvent::Next => {
+                self.menu.next();
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Prev => {
+                self.menu.prev();
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::GoToTop => {
+                self.menu.to_top();
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::GoToBottom => {
+                self.menu.to_bottom();
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::GoTo(i) => {
+                self.menu.to(*i);
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Search(s) => {
+                self.menu.search(s);
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            // Select goes into directories and adds files. With marks, it
+            // adds everything marked
+            ComponentEvent::Select => match self.entries.get(self.menu.selection) {
+                Some(DirEntry::Directory(dir)) if !self.menu.has_marks() => {
+                    if self.is_up(self.menu.selection) {
+                        self.came_from = Some(self.path.clone());
+                    }
+
+                    tx.send(self.spawn_list_event(dir)).unwrap();
+                },
+                Some(_) => {
+                    tx.send(Event::ToMpd(
+                        MpdEvent::AddPaths(self.marked_paths())
+                    )).unwrap();
+
+                    self.menu.clear_marks();
+                    tx.send(self.spawn_needs_draw_event()).unwrap();
+                },
+                None => (),
+            },
+            // Start adds whatever is under the cursor, whole directories
+            // included
+            ComponentEvent::Start => {
+                tx.send(Event::ToMpd(
+                    MpdEvent::AddPaths(self.marked_paths())
+                )).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Directory(path, entries) => {
+                self.set_entries(path, entries);
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            // Directories come from mpd rather than the library, so list
+            // again as it loads. The listing updates the tracks too
+            ComponentEvent::Database => {
+                tx.send(self.spawn_list_event(&self.path)).unwrap();
+            },
+            ComponentEvent::LostMpdConnection => {
+                self.set_entries("", &Vec::new());
+                tx.send(self.spawn_update_event(&Library::new())).unwrap();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            _ => (),
+        }
+    }
+
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw(surface, focus);
+    }
+}
+
+/* Every track in the library under entry */
+fn tracks_in(entry: &DirEntry, library: &Library) -> Vec<SongId> {
+    match entry {
+        DirEntry::Directory(dir) => library.under(dir),
+        DirEntry::File(file) => library.find_file(file).into_iter().collect(),
+    }
+}
+
+fn parent(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(i) => &path[..i],
+        None => "",
+    }
+}
+
+fn basename(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(i) => &path[i + 1..],
+        None => path,
+    }
+}
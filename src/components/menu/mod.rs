@@ -22,11 +22,34 @@ pub mod playlist_menu;
 pub mod tag_menu;
 pub mod track_menu;
 pub mod style_menu;
+pub mod directory_browser;
+pub mod table;
+
+use std::collections::BTreeSet;
+
+use mpd::Song;
 
 use crate::color::Color;
-use termion::{cursor, style, color};
+use crate::format;
+use crate::render::{Style, Surface};
+use table::Table;
 use unicode_truncate::{UnicodeTruncateStr, Alignment};
 
+#[cfg(test)]
+mod tests;
+
+/* The colors, title and alignments every kind of menu takes from the layout */
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct MenuStyle {
+    pub color: Color,
+    pub focus_color: Color,
+    pub title: Option<String>,
+    pub title_alignment: Alignment,
+    pub menu_alignment: Alignment,
+}
+
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct Menu {
@@ -38,74 +61,245 @@ pub struct Menu {
     pub title: Option<String>,
     pub title_alignment: Alignment,
     pub menu_alignment: Alignment,
+    pub table: Option<Table>,
+    pub marks: BTreeSet<usize>,
+    pub visual: Option<usize>,
 }
 
 impl Menu {
-    pub fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        let mut cur_y = y;
+    pub fn new(name: &str, style: MenuStyle, table: Option<Table>) -> Menu {
+        Menu {
+            name: name.to_string(),
+            selection: 0,
+            items: Vec::new(),
+            color: style.color,
+            focus_color: style.focus_color,
+            title: style.title,
+            title_alignment: style.title_alignment,
+            menu_alignment: style.menu_alignment,
+            table,
+            marks: BTreeSet::new(),
+            visual: None,
+        }
+    }
+
+    pub fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.draw_header(surface, focus);
+
+        let color = self.color(focus);
+        let w = surface.width();
 
-        let mut buffer = String::new();
+        let first = self.first_visible(surface.height());
 
+        for (y, i) in (self.header_height()..surface.height()).zip(first..) {
+            match self.line(i, w, color) {
+                Some(spans) => surface.put_spans(0, y, &spans, self.style(i, color)),
+                None => surface.put(0, y, &" ".repeat(w as usize), Style::new(color)),
+            };
+        }
+    }
 
-        let mut i = self.first_visible(h);
+    /* Draws the title and column headers, whichever are turned on */
+    pub fn draw_header(&self, surface: &mut Surface, focus: bool) {
+        let style = Style::new(self.color(focus));
+        let w = surface.width();
+        let mut y = 0;
 
         if let Some(title) = &self.title {
-            buffer.push_str(
-                &format!(
-                    "{}{}{}{}{}{}",
-                    color::Fg(self.color(focus)),
-                    cursor::Goto(x, y),
-                    title.unicode_pad(w as usize, self.title_alignment, true),
-                    cursor::Goto(x, y + 1),
-                    "─".repeat(w as usize),
-                    style::Reset,
-                )
-            );
-
-            cur_y = cur_y + 2;
-        }
-
-        buffer.push_str(&format!("{}", color::Fg(self.color(focus))));
-
-        for line in cur_y..(y + h) {
-            if let Some(s) = self.items.get(i) {
-                if self.selection == i {
-                    buffer.push_str(
-                        &format!(
-                            "{}{}{}{}{}",
-                            style::Invert,
-                            cursor::Goto(x, line),
-                            s.unicode_pad(w as usize, self.menu_alignment, true),
-                            style::Reset,
-                            color::Fg(self.color(focus)),
-                        )
-                    );
-                } else {
-                    buffer.push_str(
-                        &format!(
-                            "{}{}",
-                            cursor::Goto(x, line),
-                            s.unicode_pad(w as usize, self.menu_alignment, true),
-                        )
-                    );
-                }
-            } else {
-                buffer.push_str(
-                    &format!(
-                        "{}{}",
-                        cursor::Goto(x, line),
-                        " ".unicode_pad(w as usize, self.menu_alignment, true),
-                    ),
+            surface.put(0, 0, &title.unicode_pad(w as usize, self.title_alignment, true), style);
+            surface.put(0, 1, &"─".repeat(w as usize), style);
+
+            y += 2;
+        }
+
+        if let Some(table) = &self.table {
+            if table.header {
+                surface.put_spans(
+                    0,
+                    y,
+                    &table.header_line(w, style.fg),
+                    Style { bold: true, ..style },
                 );
             }
+        }
+    }
+
+    /* Rows taken up by draw_header */
+    pub fn header_height(&self) -> u16 {
+        let title = match self.title {
+            Some(_) => 2,
+            None => 0,
+        };
+
+        let header = match &self.table {
+            Some(table) if table.header => 1,
+            _ => 0,
+        };
+
+        title + header
+    }
+
+    /* Item i padded out to w, laid out in columns if there is a table.
+     * color is the color the line is drawn in, unless a column has its own */
+    pub fn line(&self, i: usize, w: u16, color: Color) -> Option<Vec<(String, Color)>> {
+        match &self.table {
+            Some(table) => table.row_line(i, w, color),
+            None => self.items.get(i).map(|s| vec![(
+                s.unicode_pad(w as usize, self.menu_alignment, true).to_string(),
+                color,
+            )]),
+        }
+    }
+
+    /* Sets songs as the menu's items, using the table's columns if there is
+     * one and fmt otherwise */
+    pub fn set_songs(&mut self, songs: &[&Song], fmt: &Option<String>) {
+        self.items = match &mut self.table {
+            Some(table) => table.set_songs(songs),
+            None => songs.iter()
+                .map(|s| format::song(fmt, s))
+                .collect(),
+        };
+    }
+
+    /* Replaces the items, keeping the cursor and marks on the same items
+     * where they are still there */
+    pub fn replace_items(&mut self, items: Vec<String>) {
+        let find = |i: &usize| {
+            let item = self.items.get(*i)?;
+            items.iter().position(|s| s == item)
+        };
+
+        let selection = find(&self.selection).unwrap_or(0);
+        let marks = self.marks.iter().filter_map(find).collect();
+        let visual = self.visual.as_ref().and_then(find);
+
+        self.items = items;
+        self.selection = selection;
+        self.marks = marks;
+        self.visual = visual;
+    }
+
+    /* Styling for item i in color. The cursor is inverted and marked items
+     * are underlined */
+    pub fn style(&self, i: usize, color: Color) -> Style {
+        Style {
+            invert: self.selection == i,
+            underline: self.is_marked(i),
+            ..Style::new(color)
+        }
+    }
+
+    /* Starts visual mode at the cursor, or leaves it, dropping the range */
+    pub fn toggle_visual(&mut self) {
+        self.visual = match self.visual {
+            Some(_) => None,
+            None => Some(self.selection),
+        };
+    }
 
-            i = i + 1;
+    pub fn toggle_mark(&mut self) {
+        if self.selection < self.items.len()
+            && !self.marks.remove(&self.selection)
+        {
+            self.marks.insert(self.selection);
         }
+    }
 
-        buffer.push_str(&format!("{}", style::Reset));
+    pub fn clear_marks(&mut self) {
+        self.marks.clear();
+        self.visual = None;
+    }
+
+    /* Moves the cursor and every mark by offset, for when the items
+     * themselves have been moved */
+    pub fn shift(&mut self, offset: i64) {
+        let shift = |i: usize| (i as i64 + offset).max(0) as usize;
 
-        print!("{}", buffer);
+        self.marks = self.marks.iter().map(|i| shift(*i)).collect();
+        self.visual = self.visual.map(shift);
+        self.selection = shift(self.selection);
     }
+
+    /* Moves the marked items by offset. Returns the moves to make to the
+     * underlying list, in the order they need to be made, or nothing if the
+     * items would go past either end */
+    pub fn move_marked(&mut self, offset: i64) -> Vec<(usize, usize)> {
+        let mut marked = self.marked();
+
+        let in_bounds = match (marked.first(), marked.last()) {
+            (Some(first), Some(last)) =>
+                *first as i64 + offset >= 0
+                    && (*last as i64 + offset) < self.items.len() as i64,
+            _ => false,
+        };
+
+        if !in_bounds {
+            return Vec::new();
+        }
+
+        // Move the item nearest the destination first so the rest don't
+        // jump over each other
+        if offset > 0 {
+            marked.reverse();
+        }
+
+        self.shift(offset);
+
+        marked.into_iter()
+            .map(|i| (i, (i as i64 + offset) as usize))
+            .collect()
+    }
+
+    /* Drops marks past the end of the menu, for when items were removed */
+    pub fn trim_marks(&mut self) {
+        let len = self.items.len();
+
+        self.marks.retain(|i| *i < len);
+
+        if len == 0 {
+            self.visual = None;
+        } else {
+            self.visual = self.visual.map(|i| i.min(len - 1));
+        }
+    }
+
+    pub fn has_marks(&self) -> bool {
+        !self.marks.is_empty() || self.visual.is_some()
+    }
+
+    pub fn is_marked(&self, i: usize) -> bool {
+        self.marks.contains(&i) || match self.visual {
+            Some(anchor) => (anchor.min(self.selection)..=anchor.max(self.selection))
+                .contains(&i),
+            None => false,
+        }
+    }
+
+    /* Items to act on, in order. This is everything marked or in the visual
+     * range, or just the cursor if nothing is */
+    pub fn marked(&self) -> Vec<usize> {
+        if !self.has_marks() {
+            return match self.selection < self.items.len() {
+                true => vec![self.selection],
+                false => Vec::new(),
+            };
+        }
+
+        (0..self.items.len())
+            .filter(|i| self.is_marked(*i))
+            .collect()
+    }
+
+    /* The marked items, or n items from the selection on if none are
+     * marked */
+    pub fn marked_or_next(&self, n: usize) -> Vec<usize> {
+        match self.has_marks() {
+            true => self.marked(),
+            false => (self.selection..self.items.len()).take(n).collect(),
+        }
+    }
+
     pub fn color(&self, focus: bool) -> Color {
         if focus {
             self.focus_color
@@ -169,10 +363,12 @@ impl Menu {
         &self,
         h: u16,
     ) -> usize {
-        let h = match self.title {
-            Some(_) => h - 2,
-            None => h,
-        };
+        let h = h.saturating_sub(self.header_height());
+
+        // No room for any items below the header
+        if h == 0 {
+            return self.selection;
+        }
 
         let mut center = h / 2;
         if h % 2 == 0 {
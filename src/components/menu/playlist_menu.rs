@@ -19,68 +19,55 @@ along with Shellbird; see the file COPYING.  If not see
 
 use std::sync::mpsc;
 
-use unicode_truncate::Alignment;
-
 use crate::event::*;
+use crate::render::Surface;
 use crate::GlobalState;
 use crate::playlist::Playlist;
-use crate::components::{Component, Components, menu::Menu};
-use crate::color::Color;
+use crate::components::{Component, Components, menu::{Menu, MenuStyle}};
+use crate::format;
 
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct PlaylistMenu {
     menu: Menu,
     playlists: Vec<Playlist>,
+    format: Option<String>,
 }
 
 impl PlaylistMenu {
     pub fn enumed(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
     ) -> Components {
         Components::PlaylistMenu(
             PlaylistMenu::new(
                 name,
-                color,
-                focus_color,
-                title,
-                title_alignment,
-                menu_alignment,
+                style,
+                format,
             )
         )
     }
 
     pub fn new(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
     ) -> PlaylistMenu {
         PlaylistMenu {
             playlists: Vec::new(),
-            menu: Menu {
-                title,
-                name: name.to_string(),
-                color,
-                focus_color,
-                selection: 0,
-                items: Vec::new(),
-                title_alignment,
-                menu_alignment,
-            },
+            format,
+            menu: Menu::new(name, style, None),
         }
     }
 
     fn update_menu_items(&mut self) {
         self.menu.items = self.playlists.iter()
-            .map(|pl| pl.name.clone()).collect();
+            .map(|pl| match &self.format {
+                Some(fmt) => format::expand(fmt, |key| pl.placeholder(key)),
+                None => pl.name.clone(),
+            }).collect();
+        self.menu.trim_marks();
     }
 
     fn spawn_update_event(&self) -> Event {
@@ -130,18 +117,65 @@ impl Component for PlaylistMenu {
                 tx.send(self.spawn_update_event()).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
             ComponentEvent::Search(s) => {
                 self.menu.search(s);
                 tx.send(self.spawn_update_event()).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::Select => {
-                let playlists = self.playlists.get(self.menu.selection).unwrap()
-                    .tracks.clone();
+                let files = self.menu.marked().iter()
+                    .filter_map(|i| self.playlists.get(*i))
+                    .flat_map(|pl| pl.tracks.iter().map(|song| song.file.clone()))
+                    .collect();
+
+                let event = Event::ToMpd(MpdEvent::AddToQueue(files));
 
-                let event = Event::ToMpd(MpdEvent::AddToQueue(playlists));
+                tx.send(event).unwrap();
 
-                tx.send(event).unwrap()
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::AddToPlaylist(name) => {
+                let files = self.menu.marked().iter()
+                    .filter_map(|i| self.playlists.get(*i))
+                    .flat_map(|pl| pl.tracks.iter().map(|song| song.file.clone()))
+                    .collect();
+
+                tx.send(Event::ToMpd(
+                    MpdEvent::AddToPlaylist(name.to_string(), files)
+                )).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::RenamePlaylist(name) => {
+                if let Some(pl) = self.playlists.get(self.menu.selection) {
+                    tx.send(Event::ToMpd(
+                        MpdEvent::RenamePlaylist(pl.name.clone(), name.to_string())
+                    )).unwrap();
+                }
+            },
+            ComponentEvent::RemovePlaylist => {
+                for pl in self.menu.marked().iter().filter_map(|i| self.playlists.get(*i)) {
+                    tx.send(Event::ToMpd(
+                        MpdEvent::RemovePlaylist(pl.name.clone())
+                    )).unwrap();
+                }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::Playlist(playlists) => {
                 self.playlists = playlists.clone();
@@ -155,15 +189,12 @@ impl Component for PlaylistMenu {
                 tx.send(self.spawn_update_event()).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             _ => (),
         }
 
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        self.menu.draw(x, y, w, h, focus);
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw(surface, focus);
     }
 }
@@ -20,84 +20,132 @@ along with Shellbird; see the file COPYING.  If not see
 use mpd::Song;
 use std::sync::mpsc;
 
-use termion::{color, cursor, style};
 use crate::event::*;
 use crate::GlobalState;
 use crate::color::Color;
-use crate::components::{Component, Components, menu::Menu};
-use unicode_truncate::{UnicodeTruncateStr, Alignment};
+use crate::components::{Component, Components, menu::{Menu, MenuStyle, table::Table}};
+use crate::render::{Style, Surface};
+use unicode_truncate::UnicodeTruncateStr;
 
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct Queue {
     tracks: Vec<Song>,
     menu: Menu,
-    now_playing: Option<Song>,
+    // Queue position of the currently playing track
+    now_playing: Option<u32>,
+    format: Option<String>,
+    playing_color: Option<Color>,
+    playing_marker: Option<String>,
+    follow_playing: bool,
 }
 
 impl Queue {
     pub fn enumed(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
+        table: Option<Table>,
+        playing_color: Option<Color>,
+        playing_marker: Option<String>,
+        follow_playing: bool,
     ) -> Components {
         Components::Queue(
             Queue::new(
                 name,
-                color,
-                focus_color,
-                title,
-                title_alignment,
-                menu_alignment,
+                style,
+                format,
+                table,
+                playing_color,
+                playing_marker,
+                follow_playing,
             )
         )
     }
 
     pub fn new(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
+        table: Option<Table>,
+        playing_color: Option<Color>,
+        playing_marker: Option<String>,
+        follow_playing: bool,
     ) -> Queue {
         Queue {
             tracks: Vec::new(),
+            format,
             now_playing: None,
-            menu: Menu {
-                title,
-                name: name.to_string(),
-                focus_color,
-                color,
-                selection: 0,
-                items: Vec::new(),
-                title_alignment,
-                menu_alignment,
-            },
+            playing_color,
+            playing_marker,
+            follow_playing,
+            menu: Menu::new(name, style, table),
         }
     }
 
     fn set_now_playing(&mut self, target: &Option<Song>) {
-        match target {
-            Some(target) => self.now_playing = Some(target.clone()),
-            None => self.now_playing = None,
+        let prev = self.playing();
+
+        self.now_playing = match target {
+            Some(target) => target.place.map(|p| p.pos),
+            None => None,
+        };
+
+        // Only follow when the track changes, so pausing or seeking doesn't
+        // pull the selection away from where the user left it
+        if self.follow_playing && self.playing() != prev {
+            if let Some(i) = self.playing() {
+                self.menu.to(i);
+            }
         }
     }
 
+    /* Index of the currently playing track in the queue */
+    fn playing(&self) -> Option<usize> {
+        let pos = self.now_playing?;
+
+        self.tracks.iter()
+            .position(|s| s.place.map(|p| p.pos) == Some(pos))
+    }
+
     fn update_items(&mut self, tracks: &Vec<Song>) {
         self.tracks = tracks.clone();
         self.update_menu_items();
+
+        // Keep selection on the queue if tracks were removed from the end
+        if self.menu.selection >= self.tracks.len() {
+            self.menu.selection = self.tracks.len().saturating_sub(1);
+        }
+
+        self.menu.trim_marks();
+    }
+
+    /* Queue positions of the marked tracks, or the selected track if none
+     * are marked */
+    fn marked_pos(&self) -> Vec<u32> {
+        self.menu.marked().iter()
+            .filter_map(|i| self.tracks.get(*i)?.place)
+            .map(|p| p.pos)
+            .collect()
+    }
+
+    /* Moves the marked tracks by offset, taking the selection and marks with
+     * them */
+    fn move_marked(&mut self, offset: i64, tx: &mpsc::Sender<Event>) {
+        let moves: Vec<(u32, u32)> = self.menu.move_marked(offset).into_iter()
+            .map(|(from, to)| (from as u32, to as u32))
+            .collect();
+
+        if !moves.is_empty() {
+            tx.send(Event::ToMpd(MpdEvent::MoveMany(moves))).unwrap();
+        }
+
+        tx.send(self.spawn_needs_draw_event()).unwrap();
     }
 
     fn update_menu_items(&mut self) {
-        self.menu.items = self.tracks.iter()
-            .map(|s| match &s.title {
-                Some(title) => title.to_string(),
-                None => "<Empty>".to_string(),
-            }).collect();
+        let songs: Vec<&Song> = self.tracks.iter().collect();
+        self.menu.set_songs(&songs, &self.format);
     }
 }
 
@@ -132,17 +180,105 @@ impl Component for Queue {
                 self.menu.to(*i);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::GoToPlaying => {
+                if let Some(i) = self.playing() {
+                    self.menu.to(i);
+                    tx.send(self.spawn_needs_draw_event()).unwrap();
+                }
+            },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
             ComponentEvent::Search(s) => {
                 self.menu.search(s);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::Select => {
-                if let Some(song) = self.tracks.get(self.menu.selection) {
+                let first = self.menu.marked().first()
+                    .and_then(|i| self.tracks.get(*i)?.place);
+
+                if let Some(place) = first {
+                    tx.send(Event::ToMpd(MpdEvent::PlayPos(place.pos))).unwrap()
+                }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Delete(n) => {
+                let marked: Vec<u32> = self.menu.marked_or_next(*n).iter()
+                    .filter_map(|i| self.tracks.get(*i)?.place)
+                    .map(|p| p.pos)
+                    .collect();
+
+                if !marked.is_empty() {
+                    tx.send(Event::ToMpd(MpdEvent::Delete(marked))).unwrap();
+                }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::AddToPlaylist(name) => {
+                let files = self.menu.marked().iter()
+                    .filter_map(|i| self.tracks.get(*i))
+                    .map(|song| song.file.clone())
+                    .collect();
+
+                tx.send(Event::ToMpd(
+                    MpdEvent::AddToPlaylist(name.to_string(), files)
+                )).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::MoveUp => self.move_marked(-1, &tx),
+            ComponentEvent::MoveDown => self.move_marked(1, &tx),
+            // With marks, crop keeps the marked tracks instead of the playing
+            // one
+            ComponentEvent::Crop if self.menu.has_marks() => {
+                let marked = self.marked_pos();
+
+                let unmarked: Vec<u32> = self.tracks.iter()
+                    .filter_map(|s| s.place)
+                    .map(|p| p.pos)
+                    .filter(|pos| !marked.contains(pos))
+                    .collect();
+
+                if !unmarked.is_empty() {
+                    tx.send(Event::ToMpd(MpdEvent::Delete(unmarked))).unwrap();
+                }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Crop => tx.send(Event::ToMpd(MpdEvent::Crop)).unwrap(),
+            // With marks, shuffle only the range they cover
+            ComponentEvent::Shuffle if self.menu.has_marks() => {
+                let marked = self.marked_pos();
+
+                if let (Some(first), Some(last)) = (marked.first(), marked.last()) {
                     tx.send(Event::ToMpd(
-                        MpdEvent::PlayAt(song.clone())
-                    )).unwrap()
+                        MpdEvent::Shuffle(*first..*last + 1)
+                    )).unwrap();
                 }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Shuffle if !self.tracks.is_empty() => {
+                tx.send(Event::ToMpd(
+                    MpdEvent::Shuffle(0..self.tracks.len() as u32)
+                )).unwrap();
             },
+            ComponentEvent::Dedup => tx.send(Event::ToMpd(MpdEvent::Dedup)).unwrap(),
             ComponentEvent::NowPlaying(song) => {
                 self.set_now_playing(&song);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
@@ -156,73 +292,47 @@ impl Component for Queue {
                 self.update_items(&Vec::new());
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             _ => (),
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        let mut cur_y = y;
-
-        let mut buffer = String::new();
-
-        if let Some(title) = &self.menu.title {
-            buffer.push_str(
-                &format!(
-                    "{}{}{}{}{}{}",
-                    color::Fg(self.menu.color(focus)),
-                    cursor::Goto(x, y),
-                    title.unicode_pad(w as usize, self.menu.title_alignment, true),
-                    cursor::Goto(x, y + 1),
-                    "─".repeat(w as usize),
-                    style::Reset,
-                )
-            );
-
-            cur_y = cur_y + 2;
-        }
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw_header(surface, focus);
 
-        let mut i = self.menu.first_visible(h);
-        for line in cur_y..(y + h) {
-            if let Some(s) = self.menu.items.get(i) {
-                let s = s.unicode_pad(w as usize, self.menu.menu_alignment, true);
+        let w = surface.width();
 
-                if self.menu.selection == i {
-                    buffer.push_str(&format!("{}", style::Invert));
-                }
+        let (marker, marker_w) = match &self.playing_marker {
+            Some(marker) => marker.unicode_truncate(w as usize),
+            None => ("", 0),
+        };
+        let line_w = w - marker_w as u16;
 
-                if let Some(np) = &self.now_playing {
-                    if self.tracks.get(i) == Some(np) {
-                        buffer.push_str(&format!("{}", style::Bold));
-                    }
-                }
+        let playing = self.playing();
 
-                buffer.push_str(
-                    &format!(
-                        "{}{}{}{}",
-                        color::Fg(self.menu.color(focus)),
-                        cursor::Goto(x, line),
-                        s,
-                        style::Reset,
-                    )
-                );
-            } else {
-                buffer.push_str(
-                    &format!(
-                        "{}{}",
-                        cursor::Goto(x, line),
-                        " ".repeat(w as usize),
-                    ),
-                );
-            }
+        let first = self.menu.first_visible(surface.height());
 
-            i = i + 1;
-        }
+        for (y, i) in (self.menu.header_height()..surface.height()).zip(first..) {
+            let color = match self.playing_color {
+                Some(c) if playing == Some(i) => c,
+                _ => self.menu.color(focus),
+            };
+
+            if let Some(spans) = self.menu.line(i, line_w, color) {
+                let style = Style {
+                    bold: playing == Some(i),
+                    ..self.menu.style(i, color)
+                };
 
-        buffer.push_str(&format!("{}", style::Reset));
+                let marker = match playing == Some(i) {
+                    true => marker.to_string(),
+                    false => " ".repeat(marker_w),
+                };
 
-        print!("{}", buffer);
+                let x = surface.put(0, y, &marker, style);
+                surface.put_spans(x, y, &spans, style);
+            } else {
+                surface.put(0, y, &" ".repeat(w as usize), Style::default());
+            }
+        }
     }
 }
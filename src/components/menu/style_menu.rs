@@ -19,10 +19,9 @@ along with Shellbird; see the file COPYING.  If not see
 
 use std::sync::mpsc;
 
-use unicode_truncate::Alignment;
-
 use crate::event::*;
-use crate::components::{Component, Components, menu::{Parent, Menu}};
+use crate::render::Surface;
+use crate::components::{Component, Components, menu::{Parent, Menu, MenuStyle}};
 use crate::color::Color;
 use crate::styles::StyleTree;
 use crate::GlobalState;
@@ -39,21 +38,13 @@ pub struct StyleMenu {
 impl StyleMenu {
     pub fn enumed(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
         parent: Option<String>,
     ) -> Components {
         Components::StyleMenu(
             StyleMenu::new(
                 name,
-                color,
-                focus_color,
-                title,
-                title_alignment,
-                menu_alignment,
+                style,
                 parent,
             )
         )
@@ -61,27 +52,17 @@ impl StyleMenu {
 
     pub fn new(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
         parent: Option<String>
     ) -> StyleMenu {
+        let mut menu = Menu::new(name, style, None);
+        menu.items = vec!["<All>".to_string()];
+
         StyleMenu {
             parent: Parent::new(parent),
             styles: Vec::new(),
-            color,
-            menu: Menu {
-                name: name.to_string(),
-                title,
-                title_alignment,
-                menu_alignment,
-                color,
-                focus_color,
-                selection: 0,
-                items: vec![ "<All>".to_string()],
-            },
+            color: menu.color,
+            menu,
         }
     }
 
@@ -97,10 +78,24 @@ impl StyleMenu {
         }
     }
 
-    fn selection_leaf_names(&self, tree: &StyleTree) -> Vec<String> {
+    /* Marked styles, or every style if <All> is marked */
+    fn marked(&self) -> Vec<usize> {
+        let marked = self.menu.marked();
+
+        if marked.contains(&0) {
+            self.styles.clone()
+        } else {
+            marked.iter()
+                .filter_map(|i| self.styles.get(i - 1))
+                .cloned()
+                .collect()
+        }
+    }
+
+    fn marked_leaf_names(&self, tree: &StyleTree) -> Vec<String> {
         let mut ret = Vec::new();
 
-        for style in self.selection() {
+        for style in self.marked() {
             ret.append(
                 &mut tree.leaf_names(style).iter()
                     .map(|s| s.to_string())
@@ -125,6 +120,7 @@ impl StyleMenu {
 
     fn update_menu_items(&mut self, style_tree: &StyleTree) {
         self.menu.selection = 0;
+        self.menu.clear_marks();
         self.menu.items = vec!["<All>".to_string()];
         self.menu.items.append(
             &mut self.styles.iter()
@@ -179,6 +175,18 @@ impl Component for StyleMenu {
                 tx.send(self.spawn_update_event()).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
             ComponentEvent::Search(s) => {
                 self.menu.search(s);
                 tx.send(self.spawn_update_event()).unwrap();
@@ -188,10 +196,13 @@ impl Component for StyleMenu {
                 if let Some(tree) = &state.style_tree {
                     tx.send(
                         Event::ToMpd(MpdEvent::AddStyleToQueue(
-                            self.selection_leaf_names(tree)
+                            self.marked_leaf_names(tree)
                         ))
                     ).unwrap();
                 }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
             }
             ComponentEvent::UpdateRootStyleMenu if self.parent.is_none() => {
                 if let Some(tree) = &state.style_tree {
@@ -207,18 +218,15 @@ impl Component for StyleMenu {
                     tx.send(self.spawn_needs_draw_event()).unwrap();
                 }
             },
-            ComponentEvent::Database(_tracks) => {
+            ComponentEvent::Database => {
                 tx.send(self.spawn_update_event()).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             _ => (),
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        self.menu.draw(x, y, w, h, focus);
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw(surface, focus);
     }
 }
@@ -0,0 +1,176 @@
+/* Column layout for menus
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use mpd::Song;
+use unicode_truncate::{UnicodeTruncateStr, Alignment};
+
+use crate::color::Color;
+use crate::components::Size;
+use crate::format;
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Column {
+    pub title: String,
+    pub format: String,
+    pub size: Size,
+    pub alignment: Alignment,
+    pub color: Option<Color>,
+}
+
+impl Column {
+    pub fn new(
+        title: &str,
+        format: &str,
+        size: Size,
+        alignment: Alignment,
+        color: Option<Color>,
+    ) -> Column {
+        Column {
+            title: title.to_string(),
+            format: format.to_string(),
+            size,
+            alignment,
+            color,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub header: bool,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>, header: bool) -> Table {
+        Table {
+            columns,
+            header,
+            rows: Vec::new(),
+        }
+    }
+
+    /* Fills in a row for each song. Returns each row as a single line, which
+     * is what the menu searches through */
+    pub fn set_songs(&mut self, songs: &[&Song]) -> Vec<String> {
+        self.rows = songs.iter()
+            .map(|s| self.columns.iter()
+                .map(|c| format::expand(&c.format, |key| format::song_placeholder(key, s)))
+                .collect())
+            .collect();
+
+        self.rows.iter()
+            .map(|row| row.join(" "))
+            .collect()
+    }
+
+    /* Width of each column, with a space left between neighbouring columns.
+     * Remainder columns split whatever is left over */
+    pub fn widths(&self, w: u16) -> Vec<u16> {
+        let gaps = self.columns.len().saturating_sub(1) as u16;
+        let inner_w = w.saturating_sub(gaps);
+
+        let mut widths: Vec<u16> = self.columns.iter()
+            .map(|c| match c.size {
+                Size::Percent(p) => (inner_w as u32 * p as u32 / 100) as u16,
+                Size::Absolute(w) => w,
+                Size::Remainder => 0,
+            }).collect();
+
+        let remainders = self.columns.iter()
+            .filter(|c| c.size == Size::Remainder)
+            .count() as u16;
+
+        if remainders > 0 {
+            let left = inner_w.saturating_sub(widths.iter().sum());
+            let mut extra = left % remainders;
+
+            for (i, c) in self.columns.iter().enumerate() {
+                if c.size == Size::Remainder {
+                    widths[i] = left / remainders;
+
+                    if extra > 0 {
+                        widths[i] += 1;
+                        extra -= 1;
+                    }
+                }
+            }
+        }
+
+        widths
+    }
+
+    /* Column titles laid out in w columns, all in color */
+    pub fn header_line(&self, w: u16, color: Color) -> Vec<(String, Color)> {
+        self.line(
+            &self.columns.iter().map(|c| c.title.clone()).collect::<Vec<String>>(),
+            w,
+            color,
+            false,
+        )
+    }
+
+    /* Row i laid out in w columns. Columns without their own color are in
+     * row_color */
+    pub fn row_line(&self, i: usize, w: u16, row_color: Color) -> Option<Vec<(String, Color)>> {
+        self.rows.get(i).map(|row| self.line(row, w, row_color, true))
+    }
+
+    fn line(
+        &self,
+        cells: &[String],
+        w: u16,
+        color: Color,
+        column_colors: bool,
+    ) -> Vec<(String, Color)> {
+        let mut ret = Vec::new();
+        let mut used = 0;
+
+        for (i, (column, width)) in self.columns.iter()
+            .zip(self.widths(w))
+            .enumerate()
+        {
+            if i != 0 && used < w {
+                ret.push((" ".to_string(), color));
+                used += 1;
+            }
+
+            // Columns that don't fit are cut off rather than wrapped
+            let width = std::cmp::min(width, w.saturating_sub(used));
+            let cell = cells.get(i).map(|s| s.as_str()).unwrap_or("");
+            let cell = cell.unicode_pad(width as usize, column.alignment, true);
+
+            let cell_color = match column.color {
+                Some(c) if column_colors => c,
+                _ => color,
+            };
+
+            ret.push((cell.to_string(), cell_color));
+
+            used += width;
+        }
+
+        ret.push((" ".repeat(w.saturating_sub(used) as usize), color));
+
+        ret
+    }
+}
@@ -18,23 +18,19 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use std::sync::mpsc;
-use std::collections::HashSet;
 
-use mpd::Song;
-
-use unicode_truncate::Alignment;
-
-use crate::components::{Component, Components, menu::{Menu, Parent}};
+use crate::components::{Component, Components, menu::{Menu, MenuStyle, Parent}};
 use crate::event::*;
+use crate::render::Surface;
 use crate::GlobalState;
-use crate::color::Color;
+use crate::library::{Library, SongId};
 
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct TagMenu {
     tag: String,
     parent: Parent,
-    tracks: Vec<usize>,
+    tracks: Vec<SongId>,
     menu: Menu,
     multitag_separator: Option<String>,
 }
@@ -42,11 +38,7 @@ pub struct TagMenu {
 impl TagMenu {
     pub fn enumed(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
         tag: &str,
         multitag_separator: Option<String>,
         parent: Option<String>
@@ -54,11 +46,7 @@ impl TagMenu {
         Components::TagMenu(
             TagMenu::new(
                 name,
-                color,
-                focus_color,
-                title,
-                title_alignment,
-                menu_alignment,
+                style,
                 tag,
                 multitag_separator,
                 parent,
@@ -68,11 +56,7 @@ impl TagMenu {
 
     pub fn new(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
         tag: &str,
         multitag_separator: Option<String>,
         parent: Option<String>
@@ -82,16 +66,7 @@ impl TagMenu {
             tag: tag.to_string(),
             tracks: Vec::new(),
             multitag_separator,
-            menu: Menu {
-                name: name.to_string(),
-                title,
-                title_alignment,
-                menu_alignment,
-                color,
-                focus_color,
-                selection: 0,
-                items: Vec::new(),
-            },
+            menu: Menu::new(name, style, None),
         }
     }
 
@@ -103,15 +78,33 @@ impl TagMenu {
         }
     }
 
-    pub fn spawn_update_event(&self, library: &Vec<Song>) -> Event {
+    pub fn spawn_update_event(&self, library: &Library) -> Event {
         let event_tracks = self.selection(library);
 
         Event::ToAllComponents(ComponentEvent::TagMenuUpdated(self.name().to_string(), event_tracks))
     }
 
-    pub fn set_menu_items(&mut self, library: &Vec<Song>) {
-        self.menu.selection = 0;
-        self.menu.items = vec!["<All>".to_string()];
+    /* Sets the tracks to pick tags from. If these are the same tracks with
+     * more on the end, as happens while the library loads, the cursor and
+     * marks stay on the same tags */
+    pub fn set_tracks(&mut self, tracks: Vec<SongId>, library: &Library) {
+        let grew = !self.tracks.is_empty() && tracks.starts_with(&self.tracks);
+
+        self.tracks = tracks;
+
+        let items = self.menu_items(library);
+
+        if grew {
+            self.menu.replace_items(items);
+        } else {
+            self.menu.selection = 0;
+            self.menu.clear_marks();
+            self.menu.items = items;
+        }
+    }
+
+    fn menu_items(&self, library: &Library) -> Vec<String> {
+        let mut ret = vec!["<All>".to_string()];
 
         let items: Vec<String> = self.tracks.clone().iter()
             .filter(|id| library.get(**id) != None)
@@ -141,10 +134,12 @@ impl TagMenu {
         final_items.sort();
         final_items.dedup();
 
-        self.menu.items.append(&mut final_items);
+        ret.append(&mut final_items);
+
+        ret
     }
 
-    pub fn selection(&self, library: &Vec<Song>) -> Vec<usize> {
+    pub fn selection(&self, library: &Library) -> Vec<SongId> {
         if self.menu.selection == 0 {
             self.tracks.clone()
         } else {
@@ -166,20 +161,27 @@ impl TagMenu {
         }
     }
 
-    pub fn selected_tracks(&self, library: &Vec<Song>) -> Vec<Song> {
+    /* Files of the tracks matching any of the marked tags, or every track if
+     * <All> is marked */
+    pub fn selected_files(&self, library: &Library) -> Vec<String> {
+        let marked = self.menu.marked();
+        let all = marked.contains(&0);
+
+        let sel_tags: Vec<&str> = marked.iter()
+            .filter_map(|i| self.menu.items.get(*i))
+            .map(|s| s.as_str())
+            .collect();
+
         self.tracks.iter()
             .filter(|id| None != library.get(**id))
-            .filter(|id| match self.menu.selection() {
-                Some(sel_tag) => match sel_tag.as_str() {
-                    "<Empty>" => library.get(**id).unwrap().tags.get(&self.tag) == None,
-                    sel_tag => match library.get(**id).unwrap().tags.get(&self.tag) {
-                        Some(tag) => self.tag_is(tag, sel_tag),
-                        None => false,
-                    },
+            .filter(|id| all || sel_tags.iter().any(|sel_tag| match *sel_tag {
+                "<Empty>" => library.get(**id).unwrap().tags.get(&self.tag) == None,
+                sel_tag => match library.get(**id).unwrap().tags.get(&self.tag) {
+                    Some(tag) => self.tag_is(tag, sel_tag),
+                    None => false,
                 },
-                None => false,
-            })
-            .map(|id| library.get(*id).unwrap().clone())
+            }))
+            .map(|id| library.get(*id).unwrap().file.clone())
             .collect()
     }
 }
@@ -220,6 +222,18 @@ impl Component for TagMenu {
                 tx.send(self.spawn_update_event(&state.library)).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
             ComponentEvent::Search(s) => {
                 self.menu.search(s);
                 tx.send(self.spawn_update_event(&state.library)).unwrap();
@@ -228,59 +242,62 @@ impl Component for TagMenu {
             ComponentEvent::Select => {
                 tx.send(
                     Event::ToMpd(MpdEvent::AddToQueue(
-                            self.selected_tracks(&state.library))
+                            self.selected_files(&state.library))
                     )
-                ).unwrap()
+                ).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::AddToPlaylist(name) => {
+                tx.send(
+                    Event::ToMpd(MpdEvent::AddToPlaylist(
+                        name.to_string(),
+                        self.selected_files(&state.library),
+                    ))
+                ).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::StyleMenuUpdated(origin, styles) if self.parent.is(origin) => {
                 if let Some(style_tree) = &state.style_tree {
-                    let genres: HashSet<&str> = styles.iter()
-                        .map(|id| style_tree.name(*id))
-                        .collect();
-
-                    let tracks = state.library.iter().enumerate()
-                        .filter(|(_, song)| match &song.tags.get("Genre") {
-                            Some(g) => genres.contains(g.as_str()),
-                            None => false,
-                        }).map(|(i, _)| i)
+                    let mut tracks: Vec<SongId> = styles.iter()
+                        .flat_map(|id| state.library.with_tag("Genre", style_tree.name(*id)))
+                        .copied()
                         .collect();
 
-                    self.tracks = tracks;
+                    // Keep library order, so the menu can tell when the
+                    // library has just grown
+                    tracks.sort_unstable();
+                    tracks.dedup();
 
-                    self.set_menu_items(&state.library);
+                    self.set_tracks(tracks, &state.library);
                     tx.send(self.spawn_update_event(&state.library)).unwrap();
                     tx.send(self.spawn_needs_draw_event()).unwrap();
                 }
             },
             ComponentEvent::TagMenuUpdated(origin, tracks) if self.parent.is(origin) => {
-                self.tracks = tracks.clone();
-
-                self.set_menu_items(&state.library);
+                self.set_tracks(tracks.clone(), &state.library);
                 tx.send(self.spawn_update_event(&state.library)).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Database(tracks) if self.parent.is_none() => {
-                self.tracks = (0..tracks.len()).collect();
-
-                self.set_menu_items(&tracks);
+            ComponentEvent::Database if self.parent.is_none() => {
+                self.set_tracks(state.library.ids().collect(), &state.library);
 
-                tx.send(self.spawn_update_event(&tracks)).unwrap();
+                tx.send(self.spawn_update_event(&state.library)).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::LostMpdConnection => {
-                self.tracks = Vec::new();
-                self.set_menu_items(&Vec::new());
-                tx.send(self.spawn_update_event(&Vec::new())).unwrap();
+                self.set_tracks(Vec::new(), &Library::new());
+                tx.send(self.spawn_update_event(&Library::new())).unwrap();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             _ => (),
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        self.menu.draw(x, y, w, h, focus);
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw(surface, focus);
     }
 }
@@ -0,0 +1,140 @@
+/* Tests for menus
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+#[test]
+fn test_marked_defaults_to_cursor() -> Result<(), String> {
+    let mut menu = new_menu(5);
+    menu.to(3);
+
+    assert_eq!(menu.marked(), vec![3]);
+    assert_eq!(new_menu(0).marked(), Vec::<usize>::new());
+
+    Ok(())
+}
+
+#[test]
+fn test_marked_combines_marks_and_visual() -> Result<(), String> {
+    let mut menu = new_menu(10);
+
+    menu.to(8);
+    menu.toggle_mark();
+    menu.to(2);
+    menu.toggle_visual();
+    menu.next();
+    menu.next();
+
+    assert_eq!(menu.marked(), vec![2, 3, 4, 8]);
+
+    menu.toggle_visual();
+
+    assert_eq!(menu.marked(), vec![8]);
+
+    menu.to(8);
+    menu.toggle_mark();
+
+    assert_eq!(menu.marked(), vec![8]);
+    assert!(!menu.has_marks());
+
+    Ok(())
+}
+
+#[test]
+fn test_shift_and_trim_marks() -> Result<(), String> {
+    let mut menu = new_menu(5);
+
+    menu.to(1);
+    menu.toggle_mark();
+    menu.to(2);
+    menu.toggle_mark();
+    menu.shift(2);
+
+    assert_eq!(menu.marked(), vec![3, 4]);
+    assert_eq!(menu.selection, 4);
+
+    menu.items.truncate(4);
+    menu.trim_marks();
+
+    assert_eq!(menu.marked(), vec![3]);
+
+    Ok(())
+}
+
+#[test]
+fn test_move_marked() -> Result<(), String> {
+    let mut menu = new_menu(5);
+
+    menu.to(1);
+    menu.toggle_visual();
+    menu.next();
+
+    assert_eq!(menu.move_marked(1), vec![(2, 3), (1, 2)]);
+    assert_eq!(menu.marked(), vec![2, 3]);
+    assert_eq!(menu.move_marked(-1), vec![(2, 1), (3, 2)]);
+
+    menu.to(4);
+
+    assert_eq!(menu.move_marked(1), Vec::new());
+
+    Ok(())
+}
+
+#[test]
+fn test_replace_items() -> Result<(), String> {
+    let mut menu = new_menu(5);
+
+    menu.to(3);
+    menu.toggle_mark();
+    menu.to(1);
+    menu.toggle_visual();
+    menu.to(2);
+
+    menu.replace_items(
+        vec!["x", "2", "4", "3", "1"].iter().map(|s| s.to_string()).collect()
+    );
+
+    assert_eq!(menu.selection, 1);
+    assert_eq!(menu.marked(), vec![1, 2, 3, 4]);
+    assert_eq!(menu.visual, Some(4));
+
+    menu.replace_items(vec!["y".to_string()]);
+
+    assert_eq!(menu.selection, 0);
+    assert_eq!(menu.marked(), vec![0]);
+    assert_eq!(menu.visual, None);
+
+    Ok(())
+}
+
+fn new_menu(len: usize) -> Menu {
+    Menu {
+        name: "a name".to_string(),
+        selection: 0,
+        items: (0..len).map(|i| i.to_string()).collect(),
+        color: Color::Reset,
+        focus_color: Color::Reset,
+        title: None,
+        title_alignment: Alignment::Left,
+        menu_alignment: Alignment::Left,
+        table: None,
+        marks: BTreeSet::new(),
+        visual: None,
+    }
+}
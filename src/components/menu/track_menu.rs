@@ -21,39 +21,64 @@ use std::sync::mpsc;
 
 use mpd::Song;
 
-use unicode_truncate::Alignment;
-
 use crate::event::*;
-use crate::color::Color;
+use crate::render::Surface;
 use crate::GlobalState;
-use crate::components::{Component, Components, menu::{Menu, Parent}};
+use crate::library::{Library, SongId};
+use crate::playlist::Playlist;
+use crate::components::{Component, Components, menu::{Menu, MenuStyle, Parent, table::Table}};
+
+/* Where the tracks shown come from */
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum Tracks {
+    // Songs in the library, by id
+    Library(Vec<SongId>),
+    // A stored playlist, if the parent is a PlaylistMenu. Its tracks needn't
+    // be in the library
+    Playlist(Playlist),
+}
+
+impl Tracks {
+    fn len(&self) -> usize {
+        match self {
+            Tracks::Library(ids) => ids.len(),
+            Tracks::Playlist(pl) => pl.tracks.len(),
+        }
+    }
+
+    /* The song shown at index i */
+    fn get<'a>(&'a self, library: &'a Library, i: usize) -> Option<&'a Song> {
+        match self {
+            Tracks::Library(ids) => library.get(*ids.get(i)?),
+            Tracks::Playlist(pl) => pl.tracks.get(i),
+        }
+    }
+}
 
 #[derive(Debug)]
 #[derive(PartialEq)]
 pub struct TrackMenu {
     parent: Parent,
     menu: Menu,
-    tracks: Vec<Song>,
+    tracks: Tracks,
+    format: Option<String>,
 }
 
 impl TrackMenu {
     pub fn enumed(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
+        table: Option<Table>,
         parent: Option<String>,
     ) -> Components {
         Components::TrackMenu(
             TrackMenu::new(
                 name,
-                color,
-                focus_color,
-                title,
-                title_alignment,
-                menu_alignment,
+                style,
+                format,
+                table,
                 parent
             )
         )
@@ -61,44 +86,77 @@ impl TrackMenu {
 
     pub fn new(
         name: &str,
-        color: Color,
-        focus_color: Color,
-        title: Option<String>,
-        title_alignment: Alignment,
-        menu_alignment: Alignment,
+        style: MenuStyle,
+        format: Option<String>,
+        table: Option<Table>,
         parent: Option<String>
     ) -> TrackMenu {
         TrackMenu {
+            format,
             parent: Parent::new(parent),
-            tracks: Vec::new(),
-            menu: Menu {
-                name: name.to_string(),
-                title,
-                title_alignment,
-                menu_alignment,
-                color,
-                focus_color,
-                selection: 0,
-                items: Vec::new(),
-            },
+            tracks: Tracks::Library(Vec::new()),
+            menu: Menu::new(name, style, table),
+        }
+    }
+
+    /* Replaces the tracks shown. Marks are kept if this is just the same
+     * playlist being updated, or more tracks being added on the end */
+    fn set_tracks(&mut self, tracks: Tracks, library: &Library) {
+        let keep_marks = match (&self.tracks, &tracks) {
+            (Tracks::Playlist(old), Tracks::Playlist(new)) => old.name == new.name,
+            (Tracks::Library(old), Tracks::Library(new)) =>
+                !old.is_empty() && new.starts_with(old),
+            _ => false,
+        };
+
+        if !keep_marks {
+            self.menu.clear_marks();
         }
+
+        self.tracks = tracks;
+        self.update_menu_items(library);
+        self.menu.trim_marks();
     }
 
-    fn update_menu_items(&mut self) {
-        self.menu.items = self.tracks.iter()
-            .map(|s| match &s.title {
-                Some(title) => title.to_string(),
-                None => "<Empty>".to_string(),
-            }).collect();
+    fn update_menu_items(&mut self, library: &Library) {
+        let tracks = &self.tracks;
+        let songs: Vec<&Song> = (0..tracks.len())
+            .filter_map(|i| tracks.get(library, i))
+            .collect();
+
+        self.menu.set_songs(&songs, &self.format);
+    }
+
+    /* Name of the stored playlist shown, if it is one */
+    fn playlist(&self) -> Option<&str> {
+        match &self.tracks {
+            Tracks::Playlist(pl) => Some(&pl.name),
+            Tracks::Library(_) => None,
+        }
     }
 
-    fn selected_tracks(&self) -> Vec<Song> {
-        if let Some(track) = self.tracks.get(self.menu.selection) {
-            vec![track.clone()]
-        } else {
-            Vec::new()
+    /* Moves the marked tracks within their playlist */
+    fn move_marked(&mut self, offset: i64, tx: &mpsc::Sender<Event>) {
+        if let Some(name) = self.playlist().map(str::to_string) {
+            let moves: Vec<(u32, u32)> = self.menu.move_marked(offset).into_iter()
+                .map(|(from, to)| (from as u32, to as u32))
+                .collect();
+
+            if !moves.is_empty() {
+                tx.send(Event::ToMpd(MpdEvent::PlaylistMoveMany(name, moves))).unwrap();
+            }
+
+            tx.send(self.spawn_needs_draw_event()).unwrap();
         }
     }
+
+    /* Files of the marked tracks, which is all mpd needs to add them */
+    fn selected_files(&self, library: &Library) -> Vec<String> {
+        self.menu.marked().iter()
+            .filter_map(|i| self.tracks.get(library, *i))
+            .map(|song| song.file.clone())
+            .collect()
+    }
 }
 
 impl Component for TrackMenu {
@@ -114,10 +172,40 @@ impl Component for TrackMenu {
             ComponentEvent::Select => {
                 tx.send(
                     Event::ToMpd(MpdEvent::AddToQueue(
-                        self.selected_tracks()
+                        self.selected_files(&state.library)
+                    ))
+                ).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::AddToPlaylist(name) => {
+                tx.send(
+                    Event::ToMpd(MpdEvent::AddToPlaylist(
+                        name.to_string(),
+                        self.selected_files(&state.library),
                     ))
                 ).unwrap();
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Delete(n) => {
+                if let Some(name) = self.playlist() {
+                    let positions = self.menu.marked_or_next(*n).iter()
+                        .map(|i| *i as u32)
+                        .collect();
+
+                    tx.send(Event::ToMpd(
+                        MpdEvent::PlaylistDelete(name.to_string(), positions)
+                    )).unwrap();
+                }
+
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::MoveUp => self.move_marked(-1, &tx),
+            ComponentEvent::MoveDown => self.move_marked(1, &tx),
             ComponentEvent::Next => {
                 self.menu.next();
                 tx.send(self.spawn_needs_draw_event()).unwrap();
@@ -138,71 +226,55 @@ impl Component for TrackMenu {
                 self.menu.to(*i);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
+            ComponentEvent::Visual => {
+                self.menu.toggle_visual();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Mark => {
+                self.menu.toggle_mark();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::ClearMarks => {
+                self.menu.clear_marks();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
             ComponentEvent::Search(s) => {
                 self.menu.search(s);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
-            ComponentEvent::Draw(x, y, w, h, focus) =>
-                self.draw(*x, *y, *w, *h, focus == self.name()),
             ComponentEvent::Start => {
-                if let Some(track) = self.selected_tracks().first() {
-                    tx.send(
-                        Event::ToMpd(MpdEvent::PlayAt(
-                            track.clone()
-                        ))
-                    ).unwrap();
+                let first = self.menu.marked().first()
+                    .and_then(|i| self.tracks.get(&state.library, *i));
+
+                if let Some(track) = first {
+                    tx.send(Event::ToMpd(MpdEvent::PlayFile(track.file.clone()))).unwrap();
                 }
             },
             ComponentEvent::LostMpdConnection => {
-                self.tracks = Vec::new();
-                self.update_menu_items();
+                self.set_tracks(Tracks::Library(Vec::new()), &Library::new());
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::PlaylistMenuUpdated(name, pl) if self.parent.is(name) => match pl {
                 Some(pl) => {
-                    self.tracks = pl.tracks.clone();
-                    self.update_menu_items();
+                    self.set_tracks(Tracks::Playlist(pl.clone()), &state.library);
                     tx.send(self.spawn_needs_draw_event()).unwrap();
                 },
                 None => (),
             },
-            ComponentEvent::TagMenuUpdated(name, tracks) if self.parent.is(name) => {
-                self.tracks = tracks.iter()
-                    .filter(|id| state.library.get(**id) != None)
-                    .map(|id| state.library.get(*id).unwrap().clone())
-                    .collect();
-
-                self.update_menu_items();
+            ComponentEvent::DirectoryBrowserUpdated(name, tracks)
+            | ComponentEvent::TagMenuUpdated(name, tracks) if self.parent.is(name) => {
+                self.set_tracks(Tracks::Library(tracks.clone()), &state.library);
                 tx.send(self.spawn_needs_draw_event()).unwrap();
             },
             ComponentEvent::StyleMenuUpdated(name, styles) if self.parent.is(name) => {
                 if let Some(tree) = &state.style_tree {
-                    let genres = {
-                        let mut leaves = Vec::new();
-
-                        for style in styles {
-                            leaves.append(
-                                &mut tree.leaf_names(*style).iter()
-                                    .map(|s| s.to_string())
-                                    .collect()
-                            );
-                        }
-
-                        leaves
-                    };
-
-                    let tracks = {
-                        let mut ret = Vec::new();
-                        for genre in genres {
-                            if let Some(tracks) = tree.tracks(&Some(genre)) {
-                                ret.append(&mut tracks.clone())
-                            }
-                        }
-                        ret
-                    };
-
-                    self.tracks = tracks;
-                    self.update_menu_items();
+                    let tracks: Vec<SongId> = styles.iter()
+                        .flat_map(|style| tree.leaf_names(*style))
+                        .flat_map(|genre| state.library.with_tag("Genre", genre))
+                        .copied()
+                        .collect();
+
+                    self.set_tracks(Tracks::Library(tracks), &state.library);
                     tx.send(self.spawn_needs_draw_event()).unwrap();
                 }
             },
@@ -210,7 +282,7 @@ impl Component for TrackMenu {
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        self.menu.draw(x, y, w, h, focus);
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        self.menu.draw(surface, focus);
     }
 }
@@ -22,7 +22,7 @@ use crate::event::*;
 use crate::GlobalState;
 use std::sync::mpsc;
 use std::fmt;
-use termion::cursor;
+use crate::render::Surface;
 
 mod splitters;
 mod place_holder;
@@ -31,17 +31,25 @@ mod title_display;
 mod tag_display;
 mod menu;
 mod empty_space;
+mod seekbar;
+
+#[cfg(test)]
+mod tests;
 
 pub use place_holder::PlaceHolder;
 pub use empty_space::EmptySpace;
 pub use error_box::ErrorBox;
 pub use title_display::TitleDisplay;
 pub use tag_display::TagDisplay;
+pub use seekbar::{Seekbar, SeekbarConfig};
 pub use menu::queue::Queue;
 pub use menu::playlist_menu::PlaylistMenu;
 pub use menu::track_menu::TrackMenu;
 pub use menu::tag_menu::TagMenu;
 pub use menu::style_menu::StyleMenu;
+pub use menu::directory_browser::DirectoryBrowser;
+pub use menu::MenuStyle;
+pub use menu::table::{Column, Table};
 pub use splitters::HorizontalSplitter;
 pub use splitters::VerticalSplitter;
 pub use splitters::Splitter;
@@ -58,60 +66,13 @@ pub trait Component: fmt::Debug + PartialEq {
     fn handle(
         &mut self,
         _state: &GlobalState,
-        e: &ComponentEvent,
+        _e: &ComponentEvent,
         _tx: mpsc::Sender<Event>,
     ) {
-        match e {
-            ComponentEvent::Draw(x, y, w, h, focus) =>
-                self.draw(*x, *y, *w, *h, focus.as_str() == self.name()),
-            _ => (),
-        }
-    }
-
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool);
-
-    fn border(&self, x: u16, y: u16, w: u16, h: u16) {
-        print!("{}{}{}{}",
-               cursor::Goto(x, y),
-               "┌",
-               "─".to_string().repeat((w - 2).into()),
-               "┐",
-        );
-
-        for line in (y + 1)..(y + h - 1) {
-            print!("{}{}",
-               cursor::Goto(x, line),
-               "│",
-            );
-            print!("{}{}",
-                cursor::Goto(x + w - 1, line),
-               "│",
-            );
-        }
-
-        print!("{}{}{}{}",
-               cursor::Goto(x, y + h - 1),
-               "└",
-               "─".repeat((w - 2).into()),
-               "┘",
-        );
     }
 
-    fn clear(&self, x: u16, y: u16, w: u16, h: u16) {
-        let mut buffer = String::new();
-
-        for line in y..(y + h) {
-            buffer.push_str(
-                &format!(
-                    "{}{}",
-                    cursor::Goto(x, line),
-                    " ".repeat(w as usize),
-                ),
-            );
-        }
-
-        print!("{}", buffer);
-    }
+    /* Draws the component filling surface */
+    fn draw(&self, surface: &mut Surface, focus: bool);
 
     fn name(&self) -> &str;
 }
@@ -124,11 +85,13 @@ pub enum Components {
     ErrorBox(ErrorBox),
     TitleDisplay(TitleDisplay),
     TagDisplay(TagDisplay),
+    Seekbar(Seekbar),
     Queue(Queue),
     PlaylistMenu(PlaylistMenu),
     TrackMenu(TrackMenu),
     TagMenu(TagMenu),
     StyleMenu(StyleMenu),
+    DirectoryBrowser(DirectoryBrowser),
     Splitter(Splitters),
 }
 
@@ -146,44 +109,32 @@ impl Component for Components {
             Components::ErrorBox(c) => c.handle(state, e, tx),
             Components::TitleDisplay(c) => c.handle(state, e, tx),
             Components::TagDisplay(c) => c.handle(state, e, tx),
+            Components::Seekbar(c) => c.handle(state, e, tx),
             Components::Queue(c) => c.handle(state, e, tx),
             Components::PlaylistMenu(c) => c.handle(state, e, tx),
             Components::TrackMenu(c) => c.handle(state, e, tx),
             Components::TagMenu(c) => c.handle(state, e, tx),
             Components::StyleMenu(c) => c.handle(state, e, tx),
+            Components::DirectoryBrowser(c) => c.handle(state, e, tx),
             Components::Splitter(x) => x.handle(state, e, tx),
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        match self {
-            Components::PlaceHolder(c) => c.draw(x, y, w, h, focus),
-            Components::EmptySpace(c) => c.draw(x, y, w, h, focus),
-            Components::ErrorBox(c) => c.draw(x, y, w, h, focus),
-            Components::TitleDisplay(c) => c.draw(x, y, w, h, focus),
-            Components::TagDisplay(c) => c.draw(x, y, w, h, focus),
-            Components::Queue(c) => c.draw(x, y, w, h, focus),
-            Components::PlaylistMenu(c) => c.draw(x, y, w, h, focus),
-            Components::TrackMenu(c) => c.draw(x, y, w, h, focus),
-            Components::TagMenu(c) => c.draw(x, y, w, h, focus),
-            Components::StyleMenu(c) => c.draw(x, y, w, h, focus),
-            Components::Splitter(c) => c.draw(x, y, w, h, focus),
-        }
-    }
-
-    fn border(&self, x: u16, y: u16, w: u16, h: u16) {
+    fn draw(&self, surface: &mut Surface, focus: bool) {
         match self {
-            Components::PlaceHolder(c) => c.border(x, y, w, h),
-            Components::EmptySpace(c) => c.border(x, y, w, h),
-            Components::ErrorBox(c) => c.border(x, y, w, h),
-            Components::TitleDisplay(c) => c.border(x, y, w, h),
-            Components::TagDisplay(c) => c.border(x, y, w, h),
-            Components::Queue(c) => c.border(x, y, w, h),
-            Components::PlaylistMenu(c) => c.border(x, y, w, h),
-            Components::TrackMenu(c) => c.border(x, y, w, h),
-            Components::TagMenu(c) => c.border(x, y, w, h),
-            Components::StyleMenu(c) => c.border(x, y, w, h),
-            Components::Splitter(c) => c.border(x, y, w, h),
+            Components::PlaceHolder(c) => c.draw(surface, focus),
+            Components::EmptySpace(c) => c.draw(surface, focus),
+            Components::ErrorBox(c) => c.draw(surface, focus),
+            Components::TitleDisplay(c) => c.draw(surface, focus),
+            Components::TagDisplay(c) => c.draw(surface, focus),
+            Components::Seekbar(c) => c.draw(surface, focus),
+            Components::Queue(c) => c.draw(surface, focus),
+            Components::PlaylistMenu(c) => c.draw(surface, focus),
+            Components::TrackMenu(c) => c.draw(surface, focus),
+            Components::TagMenu(c) => c.draw(surface, focus),
+            Components::StyleMenu(c) => c.draw(surface, focus),
+            Components::DirectoryBrowser(c) => c.draw(surface, focus),
+            Components::Splitter(c) => c.draw(surface, focus),
         }
     }
 
@@ -194,11 +145,13 @@ impl Component for Components {
             Components::ErrorBox(c) => c.name(),
             Components::TitleDisplay(c) => c.name(),
             Components::TagDisplay(c) => c.name(),
+            Components::Seekbar(c) => c.name(),
             Components::Queue(c) => c.name(),
             Components::PlaylistMenu(c) => c.name(),
             Components::TrackMenu(c) => c.name(),
             Components::TagMenu(c) => c.name(),
             Components::StyleMenu(c) => c.name(),
+            Components::DirectoryBrowser(c) => c.name(),
             Components::Splitter(c) => c.name(),
         }
     }
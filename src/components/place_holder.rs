@@ -19,7 +19,7 @@ along with Shellbird; see the file COPYING.  If not see
 
 use crate::color::Color;
 use crate::components::{Components, Component};
-use termion::color;
+use crate::render::{Style, Surface};
 
 #[derive(Debug)]
 #[derive(PartialEq)]
@@ -45,13 +45,8 @@ impl PlaceHolder {
 impl Component for PlaceHolder {
     fn name(&self) -> &str { &self.name }
 
-    fn draw(
-        &self,
-        x: u16, y: u16, w: u16, h: u16,
-        _focus: bool,
-    ) {
-        print!("{}", color::Fg(self.color));
-        self.border(x, y, w, h);
-        self.clear(x + 1, y + 1, w - 2, h - 2);
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        surface.clear();
+        surface.border(Style::new(self.color));
     }
 }
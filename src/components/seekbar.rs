@@ -0,0 +1,152 @@
+/* TUI Component for displaying progress through currently playing track
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::sync::mpsc;
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+use crate::color::Color;
+use crate::components::{Component, Components};
+use crate::GlobalState;
+use crate::event::*;
+use crate::format;
+use crate::render::{Style, Surface};
+
+/* How a Seekbar looks and how far it seeks, as read from the layout */
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct SeekbarConfig {
+    pub color: Color,
+    pub empty_color: Color,
+    pub focus_color: Color,
+    pub fill: String,
+    pub empty: String,
+    pub label: bool,
+    pub step: i64,
+}
+
+impl Default for SeekbarConfig {
+    fn default() -> SeekbarConfig {
+        SeekbarConfig {
+            color: Color::Reset,
+            empty_color: Color::Reset,
+            focus_color: Color::Reset,
+            fill: "━".to_string(),
+            empty: "─".to_string(),
+            label: true,
+            step: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Seekbar {
+    name: String,
+    config: SeekbarConfig,
+    elapsed: Option<Duration>,
+    duration: Option<Duration>,
+}
+
+impl Seekbar {
+    pub fn enumed(name: &str, config: SeekbarConfig) -> Components {
+        Components::Seekbar(Seekbar::new(name, config))
+    }
+
+    pub fn new(name: &str, config: SeekbarConfig) -> Seekbar {
+        Seekbar {
+            name: name.to_string(),
+            config,
+            elapsed: None,
+            duration: None,
+        }
+    }
+
+    fn label(&self) -> String {
+        format!(
+            " {} / {}",
+            format::duration(self.elapsed.unwrap_or_default()),
+            format::duration(self.duration.unwrap_or_default()),
+        )
+    }
+
+    fn filled(&self, w: usize) -> usize {
+        match (self.elapsed, self.duration) {
+            (Some(elapsed), Some(duration)) if !duration.is_zero() =>
+                std::cmp::min(
+                    w,
+                    (w as u128 * elapsed.as_millis() / duration.as_millis())
+                        as usize,
+                ),
+            _ => 0,
+        }
+    }
+}
+
+impl Component for Seekbar {
+    fn name(&self) -> &str { &self.name }
+
+    fn handle(
+        &mut self,
+        state: &GlobalState,
+        e: &ComponentEvent,
+        tx: mpsc::Sender<Event>
+    ) {
+        match e {
+            ComponentEvent::Status(_) | ComponentEvent::Tick => {
+                self.elapsed = state.elapsed();
+                self.duration = state.duration();
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            ComponentEvent::Next => tx.send(
+                Event::ToMpd(MpdEvent::Seek(Adjustment::By(self.config.step)))
+            ).unwrap(),
+            ComponentEvent::Prev => tx.send(
+                Event::ToMpd(MpdEvent::Seek(Adjustment::By(-self.config.step)))
+            ).unwrap(),
+            ComponentEvent::LostMpdConnection => {
+                self.elapsed = None;
+                self.duration = None;
+                tx.send(self.spawn_needs_draw_event()).unwrap();
+            },
+            _ => (),
+        }
+    }
+
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        let w = surface.width();
+
+        let label = match self.config.label {
+            true => self.label(),
+            false => String::new(),
+        };
+
+        let bar_w = (w as usize).saturating_sub(label.width());
+        let filled = self.filled(bar_w);
+
+        let fill_color = match focus {
+            true => self.config.focus_color,
+            false => self.config.color,
+        };
+
+        let mut x = surface.put(0, 0, &self.config.fill.repeat(filled), Style::new(fill_color));
+        x += surface.put(x, 0, &self.config.empty.repeat(bar_w - filled), Style::new(self.config.empty_color));
+        surface.put(x, 0, &label, Style::new(self.config.color));
+    }
+}
@@ -20,6 +20,7 @@ along with Shellbird; see the file COPYING.  If not see
 
 use crate::components::*;
 use crate::GlobalState;
+use crate::render::Surface;
 
 mod vector_splitter;
 
@@ -61,6 +62,10 @@ pub trait Splitter: Component {
 
     fn contains(&self, key: &str) -> bool;
     fn children(&self) -> Vec<&str>;
+
+    /* Where each child goes when the splitter is drawn at x, y, w, h, as
+     * (key, x, y, w, h) */
+    fn panels(&self, x: u16, y: u16, w: u16, h: u16) -> Vec<(&str, u16, u16, u16, u16)>;
 }
 
 #[derive(Debug)]
@@ -83,17 +88,10 @@ impl Component for Splitters {
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        match self {
-            Splitters::VerticalSplitter(c) => c.draw(x, y, w, h, focus),
-            Splitters::HorizontalSplitter(c) => c.draw(x, y, w, h, focus),
-        }
-    }
-
-    fn border(&self, x: u16, y: u16, w: u16, h: u16) {
+    fn draw(&self, surface: &mut Surface, focus: bool) {
         match self {
-            Splitters::VerticalSplitter(c) => c.border(x, y, w, h),
-            Splitters::HorizontalSplitter(c) => c.border(x, y, w, h),
+            Splitters::VerticalSplitter(c) => c.draw(surface, focus),
+            Splitters::HorizontalSplitter(c) => c.draw(surface, focus),
         }
     }
 
@@ -140,4 +138,11 @@ impl Splitter for Splitters {
             Splitters::HorizontalSplitter(c) => c.children(),
         }
     }
+
+    fn panels(&self, x: u16, y: u16, w: u16, h: u16) -> Vec<(&str, u16, u16, u16, u16)> {
+        match self {
+            Splitters::VerticalSplitter(c) => c.panels(x, y, w, h),
+            Splitters::HorizontalSplitter(c) => c.panels(x, y, w, h),
+        }
+    }
 }
@@ -76,98 +76,33 @@ impl Splitter for HorizontalSplitter {
     fn children(&self) -> Vec<&str> {
         self.splitter.children()
     }
+
+    fn panels(&self, x: u16, y: u16, w: u16, h: u16) -> Vec<(&str, u16, u16, u16, u16)> {
+        let border = self.splitter.draw_borders as u16;
+
+        self.splitter.spans(x, w).into_iter()
+            .map(|(key, x, w)| (key, x, y + border, w, h.saturating_sub(2 * border)))
+            .collect()
+    }
 }
 
 impl Component for HorizontalSplitter {
     fn name(&self) -> &str { self.splitter.name() }
 
-    fn handle(
-        &mut self,
-        state: &GlobalState,
-        e: &ComponentEvent,
-        tx: mpsc::Sender<Event>
-    ) {
-        match e {
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, false);
-
-                let mut inner_x = *x;
-                let mut inner_y = *y;
-                let mut inner_w = *w;
-                let mut inner_h = *h;
-
-                if self.splitter.draw_borders {
-                    inner_x = inner_x + 1;
-                    inner_y = inner_y + 1;
-                    inner_w = inner_w - 2;
-                    inner_h = inner_h - 2;
-                }
-
-                let last = self.splitter.panels.len() - 1;
-
-                for (i, panel) in self.splitter.panels.iter().enumerate() {
-                    let inner_w = match panel.size {
-                        Size::Percent(p) => (inner_w * p as u16) / 100,
-                        Size::Absolute(inner_w) => inner_w,
-                        Size::Remainder => match self.splitter.draw_borders {
-                            false => w - inner_x + 1,
-                            true => w - inner_x,
-                        },
-                    };
-
-                    tx.send(
-                        Event::ToComponent(
-                            panel.key.to_string(),
-                            ComponentEvent::Draw(
-                                inner_x,
-                                inner_y,
-                                inner_w,
-                                inner_h,
-                                focus.to_string(),
-                            ),
-                        ),
-                    ).unwrap();
-
-                    inner_x = inner_x + inner_w;
-
-                    if self.splitter.draw_borders && i != last {
-                        inner_x = inner_x + 1;
-                    }
-                }
-            },
-            e => self.splitter.handle(state, e, tx),
-        }
-    }
-
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, _focus: bool) {
-        let mut inner_x = x;
-        let mut inner_w = w;
+    /* Draws the borders. Panels are drawn separately, on top */
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        surface.clear();
 
-        if self.splitter.draw_borders {
-            self.border(x, y, w, h);
-            inner_x = inner_x + 1;
-            inner_w = inner_w - 2;
+        if !self.splitter.draw_borders {
+            return;
         }
 
-        let last = self.splitter.panels.len() - 1;
-
-        for (i, panel) in self.splitter.panels.iter().enumerate() {
-            let inner_w = match panel.size {
-                Size::Percent(p) => (inner_w * p as u16) / 100,
-                Size::Absolute(inner_w) => inner_w,
-                Size::Remainder => w - inner_x + 1,
-            };
-
-            inner_x = inner_x + inner_w;
+        surface.border(Style::default());
 
-            if self.splitter.draw_borders && i != last {
-                super::draw_vertical_line(inner_x, y, h);
-                inner_x = inner_x + 1;
-            }
-        }
+        let spans = self.splitter.spans(0, surface.width());
 
-        if self.splitter.draw_borders {
-            super::draw_right_border(x + w - 1, y, h);
+        for (_, x, w) in spans.iter().take(spans.len().saturating_sub(1)) {
+            super::draw_vertical_line(surface, x + w);
         }
     }
 }
@@ -18,12 +18,8 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
-use std::sync::mpsc;
-use termion::cursor;
-
-use crate::GlobalState;
 use crate::components::{Component, ErrorBox};
-use crate::event::*;
+use crate::render::{Style, Surface};
 use super::{Panel, Splitter, Size, MoveFocusResult};
 
 mod horizontal_splitter;
@@ -41,7 +37,7 @@ struct VectorSplitter {
     draw_borders: bool,
 }
 
-impl Splitter for VectorSplitter {
+impl VectorSplitter {
     fn contains(&self, key: &str) -> bool {
         self.panels.iter()
             .map(|p| p.key.as_str())
@@ -80,44 +76,64 @@ impl Splitter for VectorSplitter {
             MoveFocusResult::Fail
         }
     }
+    /* Where each panel goes along the direction the splitter splits in, as
+     * (key, start, length). start and len are for the whole splitter,
+     * borders included */
+    fn spans(&self, start: u16, len: u16) -> Vec<(&str, u16, u16)> {
+        let border = self.draw_borders as u16;
+        let end = (start + len).saturating_sub(border);
+        let inner_len = len.saturating_sub(2 * border);
+        let last = self.panels.len().saturating_sub(1);
+
+        let mut ret = Vec::new();
+        let mut pos = start + border;
+
+        for (i, panel) in self.panels.iter().enumerate() {
+            let size = match panel.size {
+                Size::Percent(p) => (inner_len as u32 * p as u32 / 100) as u16,
+                Size::Absolute(size) => size,
+                Size::Remainder => end.saturating_sub(pos),
+            };
+
+            ret.push((panel.key.as_str(), pos, size));
+
+            pos += size;
+
+            if self.draw_borders && i != last {
+                pos += 1;
+            }
+        }
+
+        ret
+    }
 }
 
 impl Component for VectorSplitter {
     fn name(&self) -> &str { &self.name }
 
-    fn draw(&self,x: u16, y: u16, w: u16, h: u16, focus: bool) {
-        ErrorBox::new().draw(x, y, w, h, focus);
+    fn draw(&self, surface: &mut Surface, focus: bool) {
+        ErrorBox::new().draw(surface, focus);
     }
 }
 
-fn draw_vertical_line(x: u16, y: u16, h: u16) {
-    for i in 0..h {
-        print!("{}│", cursor::Goto(x, y + i));
-    }
-    print!("{}┬", cursor::Goto(x, y));
-    print!("{}┴", cursor::Goto(x, y + h - 1));
-}
+fn draw_vertical_line(surface: &mut Surface, x: u16) {
+    let h = surface.height();
 
-fn draw_horizontal_line(x: u16, y: u16, w: u16) {
-    for i in 0..w {
-        print!("{}─", cursor::Goto(x + i, y));
+    for y in 0..h {
+        surface.set(x, y, "│", Style::default());
     }
-    print!("{}├", cursor::Goto(x, y));
-    print!("{}┤", cursor::Goto(x + w - 1, y));
-}
 
-fn draw_right_border(x: u16, y: u16, h: u16) {
-    for i in 0..h {
-        print!("{}│", cursor::Goto(x, y + i));
-    }
-    print!("{}┐", cursor::Goto(x, y));
-    print!("{}┘", cursor::Goto(x, h + y - 1));
+    surface.set(x, 0, "┬", Style::default());
+    surface.set(x, h.saturating_sub(1), "┴", Style::default());
 }
 
-fn draw_bottom_border(x: u16, y: u16, w: u16) {
-    for i in 0..w {
-        print!("{}─", cursor::Goto(x + i, y));
+fn draw_horizontal_line(surface: &mut Surface, y: u16) {
+    let w = surface.width();
+
+    for x in 0..w {
+        surface.set(x, y, "─", Style::default());
     }
-    print!("{}└", cursor::Goto(x, y));
-    print!("{}┘", cursor::Goto(w + x - 1, y));
+
+    surface.set(0, y, "├", Style::default());
+    surface.set(w.saturating_sub(1), y, "┤", Style::default());
 }
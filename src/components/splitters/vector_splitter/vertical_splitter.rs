@@ -20,7 +20,6 @@ along with Shellbird; see the file COPYING.  If not see
 use super::*;
 use super::super::Splitters;
 
-use crate::GlobalState;
 use crate::components::Components;
 
 #[derive(Debug)]
@@ -59,14 +58,6 @@ impl VerticalSplitter {
 }
 
 impl Splitter for VerticalSplitter {
-    fn contains(&self, key: &str) -> bool {
-        self.splitter.contains(key)
-    }
-
-    fn children(&self) -> Vec<&str> {
-        self.splitter.children()
-    }
-
     fn focus(&self) -> Option<&str> {
         self.splitter.focus()
     }
@@ -78,99 +69,41 @@ impl Splitter for VerticalSplitter {
     fn prev(&mut self) -> MoveFocusResult {
         self.splitter.prev()
     }
-}
-
-impl Component for VerticalSplitter {
-    fn name(&self) -> &str { self.splitter.name() }
 
-    fn handle(
-        &mut self,
-        state: &GlobalState,
-        e: &ComponentEvent,
-        tx: mpsc::Sender<Event>
-    ) {
-        match e {
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, false);
-
-                let mut inner_x = *x;
-                let mut inner_y = *y;
-                let mut inner_w = *w;
-                let mut inner_h = *h;
-
-                if self.splitter.draw_borders {
-                    inner_x = inner_x + 1;
-                    inner_y = inner_y + 1;
-                    inner_w = inner_w - 2;
-                    inner_h = inner_h - 2;
-                }
-
-                let last = self.splitter.panels.len() - 1;
-
-                for (i, panel) in self.splitter.panels.iter().enumerate() {
-                    let inner_h = match panel.size {
-                        Size::Percent(p) => (inner_h * p as u16) / 100,
-                        Size::Absolute(inner_h) => inner_h,
-                        Size::Remainder => (inner_h - inner_y) + 1,
-                    };
-
-                    tx.send(
-                        Event::ToComponent(
-                            panel.key.to_string(),
-                            ComponentEvent::Draw(
-                                inner_x,
-                                inner_y,
-                                inner_w,
-                                inner_h,
-                                focus.to_string(),
-                            ),
-                        ),
-                    ).unwrap();
-
-
-                    inner_y = inner_y + inner_h;
-
-                    if self.splitter.draw_borders && i != last {
-                        inner_y = inner_y + 1;
-                    }
-                }
-            },
-            e => self.splitter.handle(state, e, tx),
-        }
+    fn contains(&self, key: &str) -> bool {
+        self.splitter.contains(key)
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, h: u16, _focus: bool) {
-        let mut inner_y = y;
-        let mut inner_h = h;
+    fn children(&self) -> Vec<&str> {
+        self.splitter.children()
+    }
 
-        if self.splitter.draw_borders {
-            self.border(x, y, w, h);
-            inner_y = inner_y + 1;
-            inner_h = inner_h - 2;
-        }
+    fn panels(&self, x: u16, y: u16, w: u16, h: u16) -> Vec<(&str, u16, u16, u16, u16)> {
+        let border = self.splitter.draw_borders as u16;
 
-        let last = self.splitter.panels.len() - 1;
+        self.splitter.spans(y, h).into_iter()
+            .map(|(key, y, h)| (key, x + border, y, w.saturating_sub(2 * border), h))
+            .collect()
+    }
+}
 
-        for (i, panel) in self.splitter.panels.iter().enumerate() {
-            let inner_h = match panel.size {
-                Size::Percent(p) => (inner_h * p as u16) / 100,
-                Size::Absolute(inner_h) => inner_h,
-                Size::Remainder => match self.splitter.draw_borders {
-                    false => inner_h - inner_y,
-                    true => (inner_h - inner_y) + 1,
-                },
-            };
+impl Component for VerticalSplitter {
+    fn name(&self) -> &str { self.splitter.name() }
 
-            inner_y = inner_y + inner_h;
+    /* Draws the borders. Panels are drawn separately, on top */
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        surface.clear();
 
-            if self.splitter.draw_borders && i != last {
-                super::draw_horizontal_line(x, inner_y, w);
-                inner_y = inner_y + 1;
-            }
+        if !self.splitter.draw_borders {
+            return;
         }
 
-        if self.splitter.draw_borders {
-            super::draw_bottom_border(x, y + h - 1, w);
+        surface.border(Style::default());
+
+        let spans = self.splitter.spans(0, surface.height());
+
+        for (_, y, h) in spans.iter().take(spans.len().saturating_sub(1)) {
+            super::draw_horizontal_line(surface, y + h);
         }
     }
 }
@@ -18,12 +18,12 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use std::sync::mpsc;
-use termion::{cursor, color};
 use unicode_truncate::{UnicodeTruncateStr, Alignment};
 use crate::GlobalState;
 use crate::color::Color;
 use crate::event::*;
 use crate::components::{Component, Components};
+use crate::render::{Style, Surface};
 
 #[derive(Debug)]
 #[derive(PartialEq)]
@@ -73,9 +73,6 @@ impl Component for TagDisplay {
         tx: mpsc::Sender<Event>
     ) {
         match e {
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             ComponentEvent::NowPlaying(song) => {
                 self.contents = match song {
                     Some(song) => match song.tags.get(&self.tag) {
@@ -93,13 +90,14 @@ impl Component for TagDisplay {
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, _h: u16, _focus: bool) {
-        print!(
-            "{}{}{}{}",
-            color::Fg(self.color),
-            cursor::Goto(x, y),
-            self.contents.unicode_pad(w as usize, self.alignment, true),
-            color::Fg(color::Reset),
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        let w = surface.width() as usize;
+
+        surface.put(
+            0,
+            0,
+            &self.contents.unicode_pad(w, self.alignment, true),
+            Style::new(self.color),
         );
     }
 }
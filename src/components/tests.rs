@@ -0,0 +1,372 @@
+/* Snapshot tests for drawing components
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use mpd::song::{Id, QueuePlace};
+use mpd::Song;
+use unicode_truncate::Alignment;
+
+use crate::color::Color;
+use crate::library::Library;
+use crate::render::Renderer;
+use crate::screen::{self, Screen};
+
+/* Draws components from root at w by h without a terminal, returning the
+ * text of each line */
+fn render(
+    root: &str,
+    components: &HashMap<String, Components>,
+    w: u16,
+    h: u16,
+) -> Vec<String> {
+    let mut renderer = Renderer::new(w, h);
+    let mut screen = Screen::new(root);
+
+    screen.draw((1, 1, w, h), components, &mut renderer);
+
+    renderer.grid().lines()
+}
+
+fn render_one(component: Components, w: u16, h: u16) -> Vec<String> {
+    let name = component.name().to_string();
+    let mut components = HashMap::new();
+    components.insert(name.clone(), component);
+
+    render(&name, &components, w, h)
+}
+
+fn song(title: &str, artist: &str) -> Song {
+    let mut song = Song::default();
+    song.file = format!("{}/{}.flac", artist, title);
+    song.title = Some(title.to_string());
+    song.tags.insert("Artist".to_string(), artist.to_string());
+    song
+}
+
+/* State with a library of n songs */
+fn library_state(n: usize) -> GlobalState {
+    let mut library = Library::new();
+    library.add(
+        (0..n).map(|i| song(&format!("Song {}", i), "Band")).collect()
+    );
+
+    let mut state = GlobalState::new();
+    state.library = Arc::new(library);
+    state
+}
+
+fn track_menu(title: Option<String>, table: Option<Table>, n: usize) -> Components {
+    let mut menu = TrackMenu::enumed(
+        "Tracks",
+        MenuStyle {
+            color: Color::White,
+            focus_color: Color::Blue,
+            title,
+            title_alignment: Alignment::Center,
+            menu_alignment: Alignment::Left,
+        },
+        None,
+        table,
+        Some("Parent".to_string()),
+    );
+
+    let (tx, _rx) = mpsc::channel();
+    menu.handle(
+        &library_state(n),
+        &ComponentEvent::TagMenuUpdated("Parent".to_string(), (0..n).collect()),
+        tx,
+    );
+
+    menu
+}
+
+#[test]
+fn test_draw_place_holder() -> Result<(), String> {
+    assert_eq!(
+        render_one(PlaceHolder::enumed("Box", Color::Red), 5, 3),
+        vec!["┌───┐", "│   │", "└───┘"],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_title_and_tag_display() -> Result<(), String> {
+    let (tx, _rx) = mpsc::channel();
+    let state = GlobalState::new();
+    let playing = ComponentEvent::NowPlaying(Some(song("Intro", "Band")));
+
+    let mut title = TitleDisplay::enumed("Title", Color::White, Alignment::Center);
+    title.handle(&state, &playing, tx.clone());
+
+    assert_eq!(render_one(title, 9, 1), vec!["  Intro  "]);
+
+    let mut artist = TagDisplay::enumed("Artist", Color::White, Alignment::Right, "Artist");
+    artist.handle(&state, &playing, tx.clone());
+
+    assert_eq!(render_one(artist, 6, 2), vec!["  Band", "      "]);
+
+    let mut album = TagDisplay::enumed("Album", Color::White, Alignment::Left, "Album");
+    album.handle(&state, &playing, tx);
+
+    assert_eq!(render_one(album, 9, 1), vec!["<Empty>  "]);
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_seekbar() -> Result<(), String> {
+    let seekbar = Seekbar::enumed(
+        "Seek",
+        SeekbarConfig {
+            color: Color::White,
+            empty_color: Color::BrightBlack,
+            focus_color: Color::Blue,
+            fill: "=".to_string(),
+            empty: "-".to_string(),
+            ..SeekbarConfig::default()
+        },
+    );
+
+    assert_eq!(render_one(seekbar, 16, 1), vec!["-- 00:00 / 00:00"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_menu_follows_cursor() -> Result<(), String> {
+    let mut menu = track_menu(Some("Tracks".to_string()), None, 10);
+
+    assert_eq!(
+        render_one(track_menu(Some("Tracks".to_string()), None, 10), 8, 5),
+        vec![" Tracks ", "────────", "Song 0  ", "Song 1  ", "Song 2  "],
+    );
+
+    let (tx, _rx) = mpsc::channel();
+    menu.handle(&GlobalState::new(), &ComponentEvent::GoToBottom, tx);
+
+    assert_eq!(
+        render_one(menu, 8, 5),
+        vec![" Tracks ", "────────", "Song 7  ", "Song 8  ", "Song 9  "],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_menu_shorter_than_header() -> Result<(), String> {
+    let mut menu = track_menu(Some("Tracks".to_string()), None, 10);
+
+    let (tx, _rx) = mpsc::channel();
+    menu.handle(&GlobalState::new(), &ComponentEvent::GoToBottom, tx);
+
+    assert_eq!(render_one(menu, 8, 1), vec![" Tracks "]);
+
+    Ok(())
+}
+
+#[test]
+fn test_track_menu_sends_files() -> Result<(), String> {
+    let state = library_state(3);
+    let mut menu = track_menu(None, None, 3);
+    let (tx, rx) = mpsc::channel();
+
+    menu.handle(&state, &ComponentEvent::Next, tx.clone());
+    menu.handle(&state, &ComponentEvent::Mark, tx.clone());
+    menu.handle(&state, &ComponentEvent::Next, tx.clone());
+    menu.handle(&state, &ComponentEvent::Mark, tx.clone());
+    menu.handle(&state, &ComponentEvent::Select, tx);
+
+    let files = rx.try_iter().find_map(|e| match e {
+        Event::ToMpd(MpdEvent::AddToQueue(files)) => Some(files),
+        _ => None,
+    });
+
+    assert_eq!(files, Some(vec!["Band/Song 1.flac".to_string(), "Band/Song 2.flac".to_string()]));
+
+    Ok(())
+}
+
+#[test]
+fn test_queue_deletes_counted_tracks_at_once() -> Result<(), String> {
+    let mut queue = Queue::enumed(
+        "Queue",
+        MenuStyle {
+            color: Color::White,
+            focus_color: Color::Blue,
+            title: None,
+            title_alignment: Alignment::Center,
+            menu_alignment: Alignment::Left,
+        },
+        None,
+        None,
+        None,
+        None,
+        false,
+    );
+
+    let songs = (0..5)
+        .map(|i| {
+            let mut song = song(&format!("Song {}", i), "Band");
+            song.place = Some(QueuePlace { id: Id(i + 1), pos: i, prio: 0 });
+            song
+        })
+        .collect();
+
+    let state = GlobalState::new();
+    let (tx, rx) = mpsc::channel();
+
+    queue.handle(&state, &ComponentEvent::Queue(songs), tx.clone());
+    queue.handle(&state, &ComponentEvent::Next, tx.clone());
+    queue.handle(&state, &ComponentEvent::Delete(3), tx.clone());
+    queue.handle(&state, &ComponentEvent::GoToBottom, tx.clone());
+    queue.handle(&state, &ComponentEvent::Delete(3), tx);
+
+    let deletes: Vec<Vec<u32>> = rx.try_iter()
+        .filter_map(|e| match e {
+            Event::ToMpd(MpdEvent::Delete(positions)) => Some(positions),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(deletes, vec![vec![1, 2, 3], vec![4]]);
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_menu_table() -> Result<(), String> {
+    let table = Table::new(
+        vec![
+            Column::new("Title", "{title}", Size::Remainder, Alignment::Left, None),
+            Column::new("Artist", "{Artist}", Size::Absolute(6), Alignment::Right, None),
+        ],
+        true,
+    );
+
+    assert_eq!(
+        render_one(track_menu(None, Some(table), 2), 14, 4),
+        vec!["Title   Artist", "Song 0    Band", "Song 1    Band", "              "],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_menu_focus_and_cursor() -> Result<(), String> {
+    let mut components = HashMap::new();
+    components.insert("Tracks".to_string(), track_menu(None, None, 3));
+
+    let mut renderer = Renderer::new(6, 3);
+    let mut screen = Screen::new("Tracks");
+    screen.draw((1, 1, 6, 3), &components, &mut renderer);
+
+    let cell = |renderer: &Renderer, x, y| renderer.grid().get(x, y).unwrap().style;
+
+    // A menu on its own has focus, so it is drawn in its focus color
+    assert_eq!(cell(&renderer, 0, 0).fg, Color::Blue);
+    assert!(cell(&renderer, 0, 0).invert);
+    assert!(!cell(&renderer, 0, 1).invert);
+
+    screen::draw_component("Tracks", (1, 1, 6, 3), "<None>", &components, &mut renderer);
+
+    assert_eq!(cell(&renderer, 0, 0).fg, Color::White);
+
+    Ok(())
+}
+
+fn splitter_layout() -> HashMap<String, Components> {
+    let mut components = HashMap::new();
+
+    components.insert(
+        "Root".to_string(),
+        VerticalSplitter::enumed(
+            "Root",
+            true,
+            vec![
+                Panel::new(Size::Absolute(1), "Title".to_string()),
+                Panel::new(Size::Remainder, "Columns".to_string()),
+            ],
+        ),
+    );
+    components.insert(
+        "Columns".to_string(),
+        HorizontalSplitter::enumed(
+            "Columns",
+            false,
+            vec![
+                Panel::new(Size::Percent(50), "Left".to_string()),
+                Panel::new(Size::Remainder, "Right".to_string()),
+            ],
+        ),
+    );
+    components.insert(
+        "Title".to_string(),
+        TitleDisplay::enumed("Title", Color::White, Alignment::Left),
+    );
+    components.insert("Left".to_string(), PlaceHolder::enumed("Left", Color::Red));
+    components.insert("Right".to_string(), PlaceHolder::enumed("Right", Color::Red));
+
+    components
+}
+
+#[test]
+fn test_draw_splitters() -> Result<(), String> {
+    let components = splitter_layout();
+
+    assert_eq!(
+        render("Root", &components, 10, 6),
+        vec![
+            "┌────────┐",
+            "│        │",
+            "├────────┤",
+            "│┌──┐┌──┐│",
+            "│└──┘└──┘│",
+            "└────────┘",
+        ],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_draw_only_dirty() -> Result<(), String> {
+    let components = splitter_layout();
+    let mut renderer = Renderer::new(10, 6);
+    let mut screen = Screen::new("Root");
+
+    screen.draw((1, 1, 10, 6), &components, &mut renderer);
+
+    renderer.area(1, 1, 10, 6).clear();
+
+    screen.needs_redraw("Left", &components);
+    screen.needs_redraw("Elsewhere", &components);
+    screen.draw((1, 1, 10, 6), &components, &mut renderer);
+
+    assert_eq!(
+        renderer.grid().lines(),
+        vec!["          ", "          ", "          ", " ┌──┐     ", " └──┘     ", "          "],
+    );
+
+    Ok(())
+}
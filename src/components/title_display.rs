@@ -18,9 +18,9 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use std::sync::mpsc;
-use termion::{color, cursor};
 use crate::color::Color;
 use crate::components::{Component, Components};
+use crate::render::{Style, Surface};
 use crate::GlobalState;
 use crate::event::*;
 use unicode_truncate::{UnicodeTruncateStr, Alignment};
@@ -59,9 +59,6 @@ impl Component for TitleDisplay {
         tx: mpsc::Sender<Event>
     ) {
         match e {
-            ComponentEvent::Draw(x, y, w, h, focus) => {
-                self.draw(*x, *y, *w, *h, focus == self.name());
-            },
             ComponentEvent::NowPlaying(song) => {
                 self.contents = match song {
                     Some(song) => match &song.title {
@@ -79,12 +76,14 @@ impl Component for TitleDisplay {
         }
     }
 
-    fn draw(&self, x: u16, y: u16, w: u16, _h: u16, _focus: bool) {
-        print!("{}{}{}{}",
-            color::Fg(self.color),
-            cursor::Goto(x, y),
-            self.contents.unicode_pad(w as usize, self.alignment, true),
-            color::Fg(Color::Reset),
+    fn draw(&self, surface: &mut Surface, _focus: bool) {
+        let w = surface.width() as usize;
+
+        surface.put(
+            0,
+            0,
+            &self.contents.unicode_pad(w, self.alignment, true),
+            Style::new(self.color),
         );
     }
 }
@@ -18,17 +18,16 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
-mod nestable_event;
-
-pub use nestable_event::NestableEvent;
-
-use mpd::Song;
+use mpd::{Song, Status};
 use termion::event::Key;
 use std::fmt;
+use std::ops::Range;
 
 use crate::playlist::Playlist;
 use crate::styles::StyleTree;
 use crate::mode::Mode;
+use crate::music::{ConnectionState, DirEntry};
+use crate::library::SongId;
 
 /* Events are sorted into different enums based on their destination
  *
@@ -44,7 +43,8 @@ use crate::mode::Mode;
 pub enum Event {
     Dummy,
 
-    BindKey(String, NestableEvent),
+    // Keys and the commands they run
+    BindKey(Vec<Key>, Vec<Vec<String>>),
 
     ToApp(AppEvent),
     ToCommandLine(CommandLineEvent),
@@ -65,13 +65,30 @@ pub enum ComponentEvent {
     GoTo(usize),
     GoToTop,
     GoToBottom,
+    GoToPlaying,
+    Delete(usize),
+    MoveUp,
+    MoveDown,
+    Crop,
+    Shuffle,
+    Dedup,
+    Visual,
+    Mark,
+    ClearMarks,
+    AddToPlaylist(String),
+    RenamePlaylist(String),
+    RemovePlaylist,
     Search(String),
     NowPlaying(Option<Song>),
+    Status(Status),
+    Tick,
     Queue(Vec<Song>),
     Playlist(Vec<Playlist>),
-    Database(Vec<Song>),
+    Database,
     PlaylistMenuUpdated(String, Option<Playlist>),
-    TagMenuUpdated(String, Vec<usize>),
+    DirectoryBrowserUpdated(String, Vec<SongId>),
+    Directory(String, Vec<DirEntry>),
+    TagMenuUpdated(String, Vec<SongId>),
     StyleMenuUpdated(String, Vec<usize>),
     UpdateRootStyleMenu,
     LostMpdConnection,
@@ -79,13 +96,23 @@ pub enum ComponentEvent {
 
 #[derive(Clone)]
 pub enum AppEvent {
-    ClearScreen,
     Resize,
     StyleTreeLoaded(Option<StyleTree>),
     SwitchScreen(String),
+    DatabaseLoading(u32),
     Database(Vec<Song>),
+    DatabaseLoaded,
+    NowPlaying(Option<Song>),
+    Status(Status),
+    Playlist(Vec<Playlist>),
+    Tick,
     LostMpdConnection,
+    MpdConnection(ConnectionState),
     DrawScreen,
+    ConnectMpd,
+    SetMpdHost(String),
+    SetMpdPort(String),
+    SetMpdPassword(String),
     Error(String),
     Quit,
 }
@@ -95,9 +122,14 @@ pub enum AppEvent {
 pub enum CommandLineEvent {
     Echo(String),
     Mode(Mode),
+    Statusline(String),
     Input(Key),
     SbrcError(usize, String),
     SbrcNotFound,
+    // A name and the commands it runs
+    Alias(String, Vec<Vec<String>>),
+    // Runs the last change again, with a new count if there is one
+    RepeatLast(Option<usize>),
 }
 
 #[derive(Debug)]
@@ -112,12 +144,47 @@ pub enum ScreenEvent {
 pub enum MpdEvent {
     TogglePause,
     ClearQueue,
-    AddToQueue(Vec<Song>),
+    // Files to add, rather than songs, so nothing is copied out of the
+    // library to send them
+    AddToQueue(Vec<String>),
     AddStyleToQueue(Vec<String>),
-    PlayAt(Song),
+    PlayPos(u32),
+    PlayFile(String),
     Random,
+    Repeat,
+    Single,
+    Consume,
+    Crossfade(i64),
+    Seek(Adjustment),
+    Volume(Adjustment),
+    Play,
+    Stop,
     Next,
     Prev,
+    Delete(Vec<u32>),
+    MoveMany(Vec<(u32, u32)>),
+    Shuffle(Range<u32>),
+    Crop,
+    Dedup,
+    SavePlaylist(String),
+    RenamePlaylist(String, String),
+    RemovePlaylist(String),
+    AddToPlaylist(String, Vec<String>),
+    PlaylistDelete(String, Vec<u32>),
+    PlaylistMoveMany(String, Vec<(u32, u32)>),
+    ListDirectory(String, String),
+    AddPaths(Vec<String>),
+}
+
+/* A value that can either be set outright or moved relative to its
+ * current value, eg. 'volume 40' vs 'volume +5' */
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Adjustment {
+    To(i64),
+    By(i64),
 }
 
 impl fmt::Debug for ComponentEvent {
@@ -125,12 +192,14 @@ impl fmt::Debug for ComponentEvent {
         match self {
             ComponentEvent::NowPlaying(i) =>
                 write!(f, "ComponentEvent::NowPlaying({:?})", i),
+            ComponentEvent::Status(s) =>
+                write!(f, "ComponentEvent::Status({:?})", s.state),
+            ComponentEvent::Tick => write!(f, "ComponentEvent::Tick"),
             ComponentEvent::Queue(s) =>
                 write!(f, "ComponentEvent::Queue({} songs)", s.len()),
             ComponentEvent::Playlist(pl) =>
                 write!(f, "ComponentEvent::Playlist({} playlists)", pl.len()),
-            ComponentEvent::Database(s) =>
-                write!(f, "ComponentEvent::Database({} songs)", s.len()),
+            ComponentEvent::Database => write!(f, "ComponentEvent::Database"),
             ComponentEvent::PlaylistMenuUpdated(t, pl) =>
                 write!(f, "ComponentEvent::PlaylistMenuUpdated({}, {} songs)",
                     t,
@@ -139,6 +208,14 @@ impl fmt::Debug for ComponentEvent {
                         None => "None",
                     }
                 ),
+            ComponentEvent::DirectoryBrowserUpdated(t, s) =>
+                write!(f, "ComponentEvent::DirectoryBrowserUpdated({}, {} songs)",
+                    t, s.len()
+                ),
+            ComponentEvent::Directory(path, entries) =>
+                write!(f, "ComponentEvent::Directory({}, {} entries)",
+                    path, entries.len()
+                ),
             ComponentEvent::TagMenuUpdated(t, s) =>
                 write!(f, "ComponentEvent::TagMenuUpdated({}, {} songs)",
                     t, s.len()
@@ -167,6 +244,23 @@ impl fmt::Debug for ComponentEvent {
             ComponentEvent::GoToTop => write!(f, "ComponentEvent::GoToTop"),
             ComponentEvent::GoToBottom =>
                 write!(f, "ComponentEvent::GoToBottom"),
+            ComponentEvent::GoToPlaying =>
+                write!(f, "ComponentEvent::GoToPlaying"),
+            ComponentEvent::Delete(n) => write!(f, "ComponentEvent::Delete({})", n),
+            ComponentEvent::MoveUp => write!(f, "ComponentEvent::MoveUp"),
+            ComponentEvent::MoveDown => write!(f, "ComponentEvent::MoveDown"),
+            ComponentEvent::Crop => write!(f, "ComponentEvent::Crop"),
+            ComponentEvent::Shuffle => write!(f, "ComponentEvent::Shuffle"),
+            ComponentEvent::Dedup => write!(f, "ComponentEvent::Dedup"),
+            ComponentEvent::Visual => write!(f, "ComponentEvent::Visual"),
+            ComponentEvent::Mark => write!(f, "ComponentEvent::Mark"),
+            ComponentEvent::ClearMarks => write!(f, "ComponentEvent::ClearMarks"),
+            ComponentEvent::AddToPlaylist(name) =>
+                write!(f, "ComponentEvent::AddToPlaylist({})", name),
+            ComponentEvent::RenamePlaylist(name) =>
+                write!(f, "ComponentEvent::RenamePlaylist({})", name),
+            ComponentEvent::RemovePlaylist =>
+                write!(f, "ComponentEvent::RemovePlaylist"),
             ComponentEvent::Search(s) =>
                 write!(f, "ComponentEvent::Search({})", s),
         }
@@ -178,12 +272,39 @@ impl fmt::Debug for MpdEvent {
         match self {
             MpdEvent::TogglePause => write!(f, "MpdEvent::TogglePause"),
             MpdEvent::ClearQueue => write!(f, "MpdEvent::ClearQueue"),
-            MpdEvent::AddToQueue(songs) => write!(f, "MpdEvent::AddToQueue({} songs)", songs.len()),
+            MpdEvent::AddToQueue(files) => write!(f, "MpdEvent::AddToQueue({} songs)", files.len()),
             MpdEvent::AddStyleToQueue(genres) => write!(f, "MpdEvent::AddStyleToQueue({} genres)", genres.len()),
-            MpdEvent::PlayAt(song) => write!(f, "MpdEvent::PlayAt({:?})", song),
+            MpdEvent::PlayPos(pos) => write!(f, "MpdEvent::PlayPos({})", pos),
+            MpdEvent::PlayFile(file) => write!(f, "MpdEvent::PlayFile({})", file),
             MpdEvent::Random => write!(f, "MpdEvent::Random"),
+            MpdEvent::Repeat => write!(f, "MpdEvent::Repeat"),
+            MpdEvent::Single => write!(f, "MpdEvent::Single"),
+            MpdEvent::Consume => write!(f, "MpdEvent::Consume"),
+            MpdEvent::Crossfade(secs) => write!(f, "MpdEvent::Crossfade({})", secs),
+            MpdEvent::Seek(a) => write!(f, "MpdEvent::Seek({:?})", a),
+            MpdEvent::Volume(a) => write!(f, "MpdEvent::Volume({:?})", a),
+            MpdEvent::Play => write!(f, "MpdEvent::Play"),
+            MpdEvent::Stop => write!(f, "MpdEvent::Stop"),
             MpdEvent::Next => write!(f, "MpdEvent::Next"),
             MpdEvent::Prev => write!(f, "MpdEvent::Prev"),
+            MpdEvent::Delete(positions) => write!(f, "MpdEvent::Delete({:?})", positions),
+            MpdEvent::MoveMany(moves) => write!(f, "MpdEvent::MoveMany({:?})", moves),
+            MpdEvent::Shuffle(range) => write!(f, "MpdEvent::Shuffle({:?})", range),
+            MpdEvent::Crop => write!(f, "MpdEvent::Crop"),
+            MpdEvent::Dedup => write!(f, "MpdEvent::Dedup"),
+            MpdEvent::SavePlaylist(name) => write!(f, "MpdEvent::SavePlaylist({})", name),
+            MpdEvent::RenamePlaylist(from, to) =>
+                write!(f, "MpdEvent::RenamePlaylist({}, {})", from, to),
+            MpdEvent::RemovePlaylist(name) => write!(f, "MpdEvent::RemovePlaylist({})", name),
+            MpdEvent::AddToPlaylist(name, files) =>
+                write!(f, "MpdEvent::AddToPlaylist({}, {} songs)", name, files.len()),
+            MpdEvent::PlaylistDelete(name, positions) =>
+                write!(f, "MpdEvent::PlaylistDelete({}, {:?})", name, positions),
+            MpdEvent::PlaylistMoveMany(name, moves) =>
+                write!(f, "MpdEvent::PlaylistMoveMany({}, {:?})", name, moves),
+            MpdEvent::ListDirectory(name, path) =>
+                write!(f, "MpdEvent::ListDirectory({}, {})", name, path),
+            MpdEvent::AddPaths(paths) => write!(f, "MpdEvent::AddPaths({:?})", paths),
         }
     }
 }
@@ -196,10 +317,22 @@ impl fmt::Debug for AppEvent {
             AppEvent::DrawScreen => write!(f, "AppEvent::DrawScreen"),
             AppEvent::StyleTreeLoaded(_) => write!(f, "AppEvent::StyleTreeLoaded"),
             AppEvent::SwitchScreen(s) => write!(f, "AppEvent::SwitchScreen({:?})", s),
+            AppEvent::DatabaseLoading(n) =>
+                write!(f, "AppEvent::DatabaseLoading({} songs)", n),
             AppEvent::Database(s) => write!(f, "AppEvent::Database({} songs)", s.len()),
+            AppEvent::DatabaseLoaded => write!(f, "AppEvent::DatabaseLoaded"),
             AppEvent::LostMpdConnection => write!(f, "AppEvent::LostMpdConnection"),
+            AppEvent::NowPlaying(s) => write!(f, "AppEvent::NowPlaying({:?})", s),
+            AppEvent::Status(s) => write!(f, "AppEvent::Status({:?})", s.state),
+            AppEvent::Playlist(pl) =>
+                write!(f, "AppEvent::Playlist({} playlists)", pl.len()),
+            AppEvent::Tick => write!(f, "AppEvent::Tick"),
+            AppEvent::MpdConnection(s) => write!(f, "AppEvent::MpdConnection({:?})", s),
             AppEvent::Quit => write!(f, "AppEvent::Quit"),
-            AppEvent::ClearScreen => write!(f, "AppEvent::ClearScreen"),
+            AppEvent::ConnectMpd => write!(f, "AppEvent::ConnectMpd"),
+            AppEvent::SetMpdHost(s) => write!(f, "AppEvent::SetMpdHost({:?})", s),
+            AppEvent::SetMpdPort(s) => write!(f, "AppEvent::SetMpdPort({:?})", s),
+            AppEvent::SetMpdPassword(_) => write!(f, "AppEvent::SetMpdPassword(<hidden>)"),
         }
     }
 }
@@ -0,0 +1,136 @@
+/* Contains functionality for formatting values for display
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::time::Duration;
+
+use mpd::Song;
+use unicode_truncate::{UnicodeTruncateStr, Alignment};
+
+#[cfg(test)]
+mod tests;
+
+/* Formats as mm:ss, or h:mm:ss if at least an hour long */
+pub fn duration(d: Duration) -> String {
+    let secs = d.as_secs();
+
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/* Replaces each {placeholder} in fmt with the value lookup gives for it.
+ *
+ * {a|b} falls back to b if lookup has nothing for a.
+ * {a:20} pads or truncates to 20 columns, {a:>20} does the same but right
+ * aligned.
+ *
+ * Placeholders lookup knows nothing about are left empty */
+pub fn expand<F>(fmt: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut ret = String::new();
+    let mut rest = fmt;
+
+    while let Some(start) = rest.find('{') {
+        ret.push_str(&rest[..start]);
+
+        match rest[start..].find('}') {
+            Some(len) => {
+                let field = &rest[start + 1..start + len];
+                ret.push_str(&expand_field(field, &lookup));
+                rest = &rest[start + len + 1..];
+            },
+            None => {
+                rest = &rest[start..];
+                break;
+            },
+        }
+    }
+
+    ret.push_str(rest);
+
+    ret
+}
+
+fn expand_field<F>(field: &str, lookup: &F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let (keys, width) = match field.rfind(':') {
+        Some(i) => match parse_width(&field[i + 1..]) {
+            Some(width) => (&field[..i], Some(width)),
+            None => (field, None),
+        },
+        None => (field, None),
+    };
+
+    let val = keys.split('|')
+        .find_map(lookup)
+        .unwrap_or_default();
+
+    match width {
+        Some((w, align)) => val.unicode_pad(w, align, true).to_string(),
+        None => val,
+    }
+}
+
+fn parse_width(s: &str) -> Option<(usize, Alignment)> {
+    match s.strip_prefix('>') {
+        Some(s) => Some((s.parse().ok()?, Alignment::Right)),
+        None => Some((s.parse().ok()?, Alignment::Left)),
+    }
+}
+
+/* Placeholder values for a song. Anything not listed is looked up as a tag,
+ * ignoring case */
+pub fn song_placeholder(key: &str, song: &Song) -> Option<String> {
+    match key {
+        "title" => song.title.clone(),
+        "name" => song.name.clone(),
+        "file" => Some(song.file.clone()),
+        "filename" => song.file.rsplit('/').next().map(|s| s.to_string()),
+        "duration" => song.duration
+            .map(|d| duration(Duration::from_secs(d.num_seconds().max(0) as u64))),
+        "track" => tag(song, "Track")
+            .map(|t| t.split('/').next().unwrap_or_default().to_string()),
+        "pos" => song.place.map(|p| (p.pos + 1).to_string()),
+        key => tag(song, key),
+    }
+}
+
+fn tag(song: &Song, key: &str) -> Option<String> {
+    song.tags.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.to_string())
+}
+
+/* Formats song for display in a menu. Without a format, this is just the
+ * title */
+pub fn song(fmt: &Option<String>, song: &Song) -> String {
+    match fmt {
+        Some(fmt) => expand(fmt, |key| song_placeholder(key, song)),
+        None => match &song.title {
+            Some(title) => title.to_string(),
+            None => "<Empty>".to_string(),
+        },
+    }
+}
@@ -0,0 +1,80 @@
+/* Tests for formatting values for display
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+fn test_song() -> Song {
+    let mut song = Song::default();
+    song.file = "music/artist/01 - track.flac".to_string();
+    song.title = Some("A Title".to_string());
+    song.tags.insert("Artist".to_string(), "An Artist".to_string());
+    song.tags.insert("Track".to_string(), "1/12".to_string());
+    song
+}
+
+#[test]
+fn test_duration() -> Result<(), String> {
+    assert_eq!("01:30", duration(Duration::from_secs(90)));
+    assert_eq!("1:01:01", duration(Duration::from_secs(3661)));
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_placeholders() -> Result<(), String> {
+    let song = test_song();
+
+    assert_eq!(
+        "1. An Artist - A Title",
+        expand("{track}. {artist} - {title}", |k| song_placeholder(k, &song)),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_fallback() -> Result<(), String> {
+    let song = test_song();
+
+    assert_eq!(
+        "01 - track.flac",
+        expand("{album|filename}", |k| song_placeholder(k, &song)),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_width() -> Result<(), String> {
+    let song = test_song();
+
+    assert_eq!(
+        "An Ar| 1|",
+        expand("{artist:5}|{track:>2}|", |k| song_placeholder(k, &song)),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_unclosed() -> Result<(), String> {
+    assert_eq!("a {b", expand("a {b", |_| Some("x".to_string())));
+
+    Ok(())
+}
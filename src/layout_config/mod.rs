@@ -77,11 +77,13 @@ fn parse_component(
             Some("PlaceHolder") => parse_place_holder(obj),
             Some("TagDisplay") => parse_tag_display(obj),
             Some("TitleDisplay") => parse_title_display(obj),
+            Some("Seekbar") => parse_seekbar(obj),
             Some("Queue") => parse_queue(obj),
             Some("PlaylistMenu") => parse_playlist_menu(obj),
             Some("TrackMenu") => parse_track_menu(obj),
             Some("TagMenu") => parse_tag_menu(obj),
             Some("StyleMenu") => parse_style_menu(obj),
+            Some("DirectoryBrowser") => parse_directory_browser(obj),
             _ => {
                 eprintln!(
                     "Error: parse_component: component with component has \
@@ -117,23 +119,22 @@ fn parse_component(
 fn parse_style_menu(obj: &Object) -> Components {
     StyleMenu::enumed(
         parse_string(obj, "name").unwrap_or("StyleMenu"),
-        parse_color(obj, "color"),
-        parse_color(obj, "focus_color"),
-        parse_optional_string(obj, "title"),
-        parse_alignment(obj, "title_alignment"),
-        parse_alignment(obj, "menu_alignment"),
+        parse_menu_style(obj),
         parse_optional_string(obj, "parent")
     )
 }
 
+fn parse_directory_browser(obj: &Object) -> Components {
+    DirectoryBrowser::enumed(
+        parse_string(obj, "name").unwrap_or("DirectoryBrowser"),
+        parse_menu_style(obj),
+    )
+}
+
 fn parse_tag_menu(obj: &Object) -> Components {
     TagMenu::enumed(
         parse_string(obj, "name").unwrap_or("TagMenu"),
-        parse_color(obj, "color"),
-        parse_color(obj, "focus_color"),
-        parse_optional_string(obj, "title"),
-        parse_alignment(obj, "title_alignment"),
-        parse_alignment(obj, "menu_alignment"),
+        parse_menu_style(obj),
         parse_string(obj, "tag").unwrap_or("Artist"),
         parse_optional_string(obj, "multitag_separator"),
         parse_optional_string(obj, "parent"),
@@ -143,11 +144,9 @@ fn parse_tag_menu(obj: &Object) -> Components {
 fn parse_track_menu(obj: &Object) -> Components {
     TrackMenu::enumed(
         parse_string(obj, "name").unwrap_or("TrackMenu"),
-        parse_color(obj, "color"),
-        parse_color(obj, "focus_color"),
-        parse_optional_string(obj, "title"),
-        parse_alignment(obj, "title_alignment"),
-        parse_alignment(obj, "menu_alignment"),
+        parse_menu_style(obj),
+        parse_optional_string(obj, "format"),
+        parse_table(obj),
         parse_optional_string(obj, "parent"),
     )
 }
@@ -155,22 +154,61 @@ fn parse_track_menu(obj: &Object) -> Components {
 fn parse_playlist_menu(obj: &Object) -> Components {
     PlaylistMenu::enumed(
         parse_string(obj, "name").unwrap_or("PlaylistMenu"),
-        parse_color(obj, "color"),
-        parse_color(obj, "focus_color"),
-        parse_optional_string(obj, "title"),
-        parse_alignment(obj, "title_alignment"),
-        parse_alignment(obj, "menu_alignment"),
+        parse_menu_style(obj),
+        parse_optional_string(obj, "format"),
     )
 }
 
 fn parse_queue(obj: &Object) -> Components {
     Queue::enumed(
         parse_string(obj, "name").unwrap_or("Queue"),
-        parse_color(obj, "color"),
-        parse_color(obj, "focus_color"),
-        parse_optional_string(obj, "title"),
-        parse_alignment(obj, "title_alignment"),
-        parse_alignment(obj, "menu_alignment"),
+        parse_menu_style(obj),
+        parse_optional_string(obj, "format"),
+        parse_table(obj),
+        obj.get("playing_color").map(|_| parse_color(obj, "playing_color")),
+        parse_optional_string(obj, "playing_marker"),
+        parse_bool(obj, "follow_playing").unwrap_or(false),
+    )
+}
+
+fn parse_menu_style(obj: &Object) -> MenuStyle {
+    MenuStyle {
+        color: parse_color(obj, "color"),
+        focus_color: parse_color(obj, "focus_color"),
+        title: parse_optional_string(obj, "title"),
+        title_alignment: parse_alignment(obj, "title_alignment"),
+        menu_alignment: parse_alignment(obj, "menu_alignment"),
+    }
+}
+
+fn parse_table(obj: &Object) -> Option<Table> {
+    match obj.get("columns") {
+        Some(JsonValue::Array(arr)) => Some(Table::new(
+            arr.iter()
+                .filter_map(|val| match val {
+                    JsonValue::Object(obj) => Some(parse_column(obj)),
+                    _ => {
+                        eprintln!("Warning: parse_table: Column is not an object. It is being skipped.");
+                        None
+                    },
+                }).collect(),
+            parse_bool(obj, "header").unwrap_or(true),
+        )),
+        Some(_) => {
+            eprintln!("Error: parse_table: columns is not an array. Ignoring it.");
+            None
+        },
+        None => None,
+    }
+}
+
+fn parse_column(obj: &Object) -> Column {
+    Column::new(
+        parse_string(obj, "title").unwrap_or(""),
+        parse_string(obj, "format").unwrap_or(""),
+        parse_size(obj),
+        parse_alignment(obj, "alignment"),
+        obj.get("color").map(|_| parse_color(obj, "color")),
     )
 }
 
@@ -191,6 +229,23 @@ fn parse_tag_display(obj: &Object) -> Components {
     )
 }
 
+fn parse_seekbar(obj: &Object) -> Components {
+    let default = SeekbarConfig::default();
+
+    Seekbar::enumed(
+        parse_string(obj, "name").unwrap_or("Seekbar"),
+        SeekbarConfig {
+            color: parse_color(obj, "color"),
+            empty_color: parse_color(obj, "empty_color"),
+            focus_color: parse_color(obj, "focus_color"),
+            fill: parse_string(obj, "fill").map_or(default.fill, str::to_string),
+            empty: parse_string(obj, "empty").map_or(default.empty, str::to_string),
+            label: parse_bool(obj, "label").unwrap_or(default.label),
+            step: parse_u64(obj, "step").map_or(default.step, |step| step as i64),
+        },
+    )
+}
+
 fn parse_string<'a>(obj: &'a Object, key: &str) -> Option<&'a str> {
     match obj.get(key) {
         Some(s) => s.as_str(),
@@ -279,6 +334,13 @@ fn parse_bool(obj: &Object, key: &str) -> Option<bool> {
     }
 }
 
+fn parse_u64(obj: &Object, key: &str) -> Option<u64> {
+    match obj.get(key) {
+        Some(val) => val.as_u64(),
+        None => None,
+    }
+}
+
 fn parse_vertical_splitter(
     obj: &Object,
     map: &mut HashMap<String, Components>,
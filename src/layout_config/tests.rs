@@ -28,7 +28,7 @@ fn test_parse_size_absolute() -> Result<(), String> {
 
     let target = Size::Absolute(10);
 
-    assert_eq!(parse_size(&input), Some(target));
+    assert_eq!(parse_size(as_object(&input)), target);
 
     Ok(())
 }
@@ -41,7 +41,7 @@ fn test_parse_size_percent() -> Result<(), String> {
 
     let target = Size::Percent(10);
 
-    assert_eq!(parse_size(&input), Some(target));
+    assert_eq!(parse_size(as_object(&input)), target);
 
     Ok(())
 }
@@ -52,14 +52,28 @@ fn test_parse_size_number() -> Result<(), String> {
         "size": 10,
     };
 
-    assert_eq!(parse_size(&input), None);
+    assert_eq!(parse_size(as_object(&input)), Size::Remainder);
 
     Ok(())
 }
 
+fn default_menu_style() -> MenuStyle {
+    MenuStyle {
+        color: Color::Reset,
+        focus_color: Color::Reset,
+        title: None,
+        title_alignment: Alignment::Left,
+        menu_alignment: Alignment::Left,
+    }
+}
+
 #[test]
 fn test_parse_style_menu() -> Result<(), String> {
-    let target = StyleMenu::enumed("a name", Color::Reset, Color::Reset, None);
+    let target = StyleMenu::enumed(
+        "a name",
+        default_menu_style(),
+        None,
+    );
 
     let input = object! {
         "component": "StyleMenu",
@@ -74,20 +88,14 @@ fn test_parse_style_menu() -> Result<(), String> {
 
 #[test]
 fn test_parse_horizontal_splitter() -> Result<(), String> {
-    let target = Some(HorizontalSplitter::enumed(
+    let target = HorizontalSplitter::enumed(
         "a name",
         true,
         vec![
-            Panel::new(
-                Size::Percent(10),
-                PlaceHolder::enumed("a name 1", Color::Yellow),
-            ),
-            Panel::new(
-                Size::Percent(3),
-                TagDisplay::enumed("a name 2", Color::Reset, Align::Left, "Genre"),
-            ),
+            Panel::new(Size::Percent(10), "a name 1".to_string()),
+            Panel::new(Size::Percent(3), "a name 2".to_string()),
         ],
-    ));
+    );
 
     let input = object! {
         "component": "HorizontalSplitter",
@@ -109,7 +117,93 @@ fn test_parse_horizontal_splitter() -> Result<(), String> {
         ],
     };
 
-    assert_eq!(target, parse_horizontal_splitter(as_object(&input)));
+    let mut map = HashMap::new();
+
+    assert_eq!(target, parse_horizontal_splitter(as_object(&input), &mut map));
+    assert_eq!(
+        Some(&PlaceHolder::enumed("a name 1", Color::Yellow)),
+        map.get("a name 1"),
+    );
+    assert_eq!(
+        Some(&TagDisplay::enumed("a name 2", Color::Reset, Alignment::Left, "Genre")),
+        map.get("a name 2"),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_seekbar() -> Result<(), String> {
+    let target = Seekbar::enumed(
+        "a name",
+        SeekbarConfig {
+            color: Color::Cyan,
+            empty_color: Color::BrightBlack,
+            focus_color: Color::Reset,
+            fill: "=".to_string(),
+            empty: "-".to_string(),
+            label: false,
+            step: 10,
+        },
+    );
+
+    let input = object! {
+        "component": "Seekbar",
+        "name": "a name",
+        "color": "Cyan",
+        "empty_color": "BrightBlack",
+        "fill": "=",
+        "empty": "-",
+        "label": false,
+        "step": 10,
+    };
+
+    let mut map = HashMap::new();
+
+    assert_eq!(
+        ("a name".to_string(), Some(target)),
+        parse_component(as_object(&input), &mut map),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_queue_columns() -> Result<(), String> {
+    let target = Queue::enumed(
+        "a name",
+        default_menu_style(),
+        None,
+        Some(Table::new(
+            vec![
+                Column::new("#", "{pos}", Size::Absolute(4), Alignment::Right, None),
+                Column::new("Title", "{title}", Size::Remainder, Alignment::Left, Some(Color::Yellow)),
+                Column::new("Time", "{duration}", Size::Percent(10), Alignment::Left, None),
+            ],
+            false,
+        )),
+        None,
+        None,
+        false,
+    );
+
+    let input = object! {
+        "component": "Queue",
+        "name": "a name",
+        "header": false,
+        "columns": [
+            { "title": "#", "format": "{pos}", "size": "4", "alignment": "Right" },
+            { "title": "Title", "format": "{title}", "color": "Yellow" },
+            { "title": "Time", "format": "{duration}", "size": "10%" },
+        ],
+    };
+
+    let mut map = HashMap::new();
+
+    assert_eq!(
+        ("a name".to_string(), Some(target)),
+        parse_component(as_object(&input), &mut map),
+    );
 
     Ok(())
 }
@@ -36,15 +36,19 @@ pub mod styles;
 pub mod command_line;
 pub mod mode;
 pub mod screen;
+pub mod format;
+pub mod library;
+pub mod render;
 
 use std::error::Error;
 use std::io::{self, Stdout, Write, BufRead, BufReader};
 use std::collections::HashMap;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::fs::File;
+use std::time::{Duration, Instant};
 
-use mpd::Song;
+use mpd::{Song, Status, State};
 
 use termion::raw::RawTerminal;
 use termion::{clear, cursor};
@@ -52,49 +56,104 @@ use termion::input::TermRead;
 
 use event::*;
 use styles::StyleTree;
-use music::{mpd_sender, mpd_listener};
+use library::Library;
+use music::{mpd_sender, mpd_listener, MpdConfig, ConnectionState};
 use command_line::CommandLine;
 use components::{Components, Component};
+use render::{Renderer, FrameTimer};
 use screen::Screen;
 
+// Shortest time between frames, so a burst of events is drawn all at once
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
 pub struct GlobalState {
     pub style_tree: Option<StyleTree>,
-    pub library: Vec<Song>,
+    // Shared rather than copied. Components look songs up here by id
+    pub library: Arc<Library>,
+    // While the library is being reloaded, how many tracks there will be.
+    // library holds what has arrived so far
+    pub loading: Option<usize>,
+    pub connection: ConnectionState,
+    pub now_playing: Option<Song>,
+    pub status: Option<Status>,
+    // Names of the stored playlists
+    pub playlists: Vec<String>,
+    status_received: Instant,
 }
 
 impl GlobalState {
     pub fn new() -> GlobalState {
         GlobalState {
             style_tree: None,
-            library: Vec::new(),
+            library: Arc::new(Library::new()),
+            loading: None,
+            connection: ConnectionState::Connecting,
+            now_playing: None,
+            status: None,
+            playlists: Vec::new(),
+            status_received: Instant::now(),
+        }
+    }
+
+    pub fn set_status(&mut self, status: Option<Status>) {
+        self.status = status;
+        self.status_received = Instant::now();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        match &self.status {
+            Some(status) => status.state == State::Play,
+            None => false,
         }
     }
+
+    /* Elapsed time of current track. Mpd only reports this when something
+     * changes, so while playing, time since the last status is added on */
+    pub fn elapsed(&self) -> Option<Duration> {
+        let status = self.status.as_ref()?;
+        let elapsed = status.elapsed?.num_milliseconds();
+        let mut elapsed = Duration::from_millis(std::cmp::max(0, elapsed) as u64);
+
+        if status.state == State::Play {
+            elapsed += self.status_received.elapsed();
+        }
+
+        match self.duration() {
+            Some(duration) => Some(std::cmp::min(elapsed, duration)),
+            None => Some(elapsed),
+        }
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        let duration = self.status.as_ref()?.duration?.num_milliseconds();
+        Some(Duration::from_millis(std::cmp::max(0, duration) as u64))
+    }
 }
 
-pub struct Shellbird<'a> {
+pub struct Shellbird {
     genres_path: Option<String>,
     sbrc_path: Option<String>,
     layout_path: Option<String>,
-    mpd_ip: &'a str,
-    mpd_port: &'a str,
+    history_path: Option<String>,
+    mpd_config: MpdConfig,
     debug: bool,
 }
 
-impl<'a> Shellbird<'a> {
+impl Shellbird {
     pub fn new(
         genres_path: Option<String>,
         sbrc_path: Option<String>,
         layout_path: Option<String>,
-        mpd_ip: &'a str,
-        mpd_port: &'a str,
+        history_path: Option<String>,
+        mpd_config: MpdConfig,
         debug: bool,
-    )  -> Shellbird<'a> {
+    )  -> Shellbird {
         Shellbird {
             genres_path,
             sbrc_path,
             layout_path,
-            mpd_ip,
-            mpd_port,
+            history_path,
+            mpd_config,
             debug,
         }
     }
@@ -109,40 +168,83 @@ impl<'a> Shellbird<'a> {
 
         let mut screen = Screen::new("Default");
 
+        let (w, h) = termion::terminal_size()?;
+        let mut renderer = Renderer::new(w, h);
+        let mut frames = FrameTimer::new(FRAME_INTERVAL);
+        frames.request();
+
         let (tx, rx) = mpsc::channel();
 
         let mut command_line = CommandLine::new(tx.clone());
+        command_line.set_components(components.keys().cloned().collect());
 
-        let mpd_tx =  mpd_sender::init_mpd_sender_thread(
-            self.mpd_ip,
-            self.mpd_port,
-            tx.clone()
-        );
+        if let Some(path) = &self.history_path {
+            command_line.load_history(path);
+        }
+
+        // Mpd threads are started by AppEvent::ConnectMpd once sbrc has had
+        // a chance to set connection options. Requests sent before then wait
+        // in this channel.
+        let (mpd_tx, mpd_rx) = mpsc::channel();
+        let mut mpd_rx = Some(mpd_rx);
+        let mut sbrc_mpd_config = MpdConfig::new();
 
         self.init_stdin_thread(tx.clone());
-        self.run_sbrc(tx.clone());
-        mpd_listener::init_mpd_listener_thread(self.mpd_ip, self.mpd_port, tx.clone());
+        self.init_tick_thread(tx.clone());
+        self.run_sbrc(&mut command_line, tx.clone());
+        tx.send(Event::ToApp(AppEvent::ConnectMpd)).unwrap();
         signals::init_listener(tx.clone());
 
         if let Some(path) = &self.genres_path {
             styles::load_style_tree_async(&path, tx.clone());
         }
 
-        print!("{}{}", cursor::Hide, clear::All);
+        print!("{}", cursor::Hide);
 
         loop {
-            command_line.draw();
+            let now = Instant::now();
+
+            if frames.due(now) {
+                let (w, h) = renderer.size();
+
+                screen.draw((1, 1, w, h.saturating_sub(1)), &components, &mut renderer);
 
-            stdout.flush().unwrap();
+                command_line.update_statusline(&state, screen.name());
+                command_line.draw(&mut renderer.area(1, h, w, 1));
 
-            let e = rx.recv()?;
+                print!("{}", renderer.flush());
+                stdout.flush().unwrap();
+
+                frames.frame(now);
+            }
+
+            let e = match frames.wait(Instant::now()) {
+                Some(timeout) => match rx.recv_timeout(timeout) {
+                    Ok(e) => e,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(e) => return Err(Box::new(e)),
+                },
+                None => rx.recv()?,
+            };
+
+            // Any event could change the command line, so each one asks for
+            // a frame. Components are only drawn again if they are dirty
+            frames.request();
 
             if self.debug {
                 eprintln!("{:?}", e);
             }
 
             match e {
-                Event::BindKey(key, e) => command_line.bind(key, e.to_event()),
+                Event::BindKey(key, commands) => command_line.bind(key, commands),
+                Event::ToComponent(name, ComponentEvent::Draw(x, y, w, h, focus)) =>
+                    screen::draw_component(
+                        &name,
+                        (x, y, w, h),
+                        &focus,
+                        &components,
+                        &mut renderer,
+                    ),
                 Event::ToComponent(name, e) => {
                     if let Some(c) = components.get_mut(&name) {
                         c.handle(&state, &e, tx.clone());
@@ -151,58 +253,125 @@ impl<'a> Shellbird<'a> {
                 Event::ToApp(e) => match e {
                     AppEvent::Quit => break,
                     AppEvent::Error(s) => eprintln!("{}", s),
-                    AppEvent::ClearScreen => print!("{}", clear::All),
-                    AppEvent::DrawScreen =>
-                        tx.send(
-                            spawn_draw_screen_event(&screen, &components)
-                        ).unwrap(),
+                    AppEvent::DrawScreen => screen.redraw_all(),
                     AppEvent::LostMpdConnection => {
-                        state.library = Vec::new();
+                        state.library = Arc::new(Library::new());
+                        state.loading = None;
+                        state.set_status(None);
+                        state.now_playing = None;
+                        state.playlists.clear();
                         tx.send(Event::ToAllComponents(
                             ComponentEvent::LostMpdConnection
                         )).unwrap();
                     },
-                    AppEvent::Database(tracks) => {
-                        if let Some(tree) = &mut state.style_tree {
-                            tree.set_tracks(tracks.clone());
-                        }
-
-                        state.library = tracks.clone();
+                    AppEvent::MpdConnection(connection) => {
+                        state.connection = connection;
+                        command_line.put_text(connection.to_string());
+                    },
+                    AppEvent::NowPlaying(song) => {
+                        state.now_playing = song.clone();
+                        tx.send(Event::ToAllComponents(
+                            ComponentEvent::NowPlaying(song)
+                        )).unwrap();
+                    },
+                    AppEvent::Status(status) => {
+                        state.set_status(Some(status.clone()));
+                        tx.send(Event::ToAllComponents(
+                            ComponentEvent::Status(status)
+                        )).unwrap();
+                    },
+                    AppEvent::Playlist(playlists) => {
+                        state.playlists = playlists.iter()
+                            .map(|pl| pl.name.clone())
+                            .collect();
+                        tx.send(Event::ToAllComponents(
+                            ComponentEvent::Playlist(playlists)
+                        )).unwrap();
+                    },
+                    AppEvent::Tick => if state.is_playing() {
+                        tx.send(Event::ToAllComponents(
+                            ComponentEvent::Tick
+                        )).unwrap();
+                    },
+                    // The old library goes right away, since ids into it mean
+                    // nothing in the new one. Components are told about every
+                    // part as it arrives, so they fill in while it loads
+                    AppEvent::DatabaseLoading(total) => {
+                        state.library = Arc::new(Library::new());
+                        state.loading = Some(total as usize);
 
                         tx.send(Event::ToAllComponents(
-                                ComponentEvent::Database(tracks))
+                                ComponentEvent::Database)
                         ).unwrap();
                     },
-                    AppEvent::SwitchScreen(name) => {
-                        screen.set(&name);
-                        tx.send(Event::ToApp(AppEvent::DrawScreen)).unwrap();
+                    AppEvent::Database(tracks) => {
+                        if let Some(total) = state.loading {
+                            let library = Arc::make_mut(&mut state.library);
+                            library.add(tracks);
+
+                            command_line.put_text(format!(
+                                "Loading library: {}/{}",
+                                library.len(),
+                                total,
+                            ));
+
+                            tx.send(Event::ToAllComponents(
+                                    ComponentEvent::Database)
+                            ).unwrap();
+                        }
+                    },
+                    AppEvent::DatabaseLoaded => {
+                        state.loading = None;
+
+                        command_line.put_text(
+                            format!("Loaded {} tracks", state.library.len())
+                        );
                     },
+                    AppEvent::SwitchScreen(name) => screen.set(&name),
                     AppEvent::StyleTreeLoaded(tree) => {
                         state.style_tree = tree;
                         tx.send(Event::ToAllComponents(
                             ComponentEvent::UpdateRootStyleMenu
                         )).unwrap();
                     },
-                    AppEvent::Resize =>
-                        tx.send(Event::ToApp(AppEvent::DrawScreen)).unwrap(),
+                    AppEvent::Resize => {
+                        let (w, h) = termion::terminal_size()?;
+                        renderer.resize(w, h);
+                        screen.redraw_all();
+                    },
+                    AppEvent::SetMpdHost(host) => sbrc_mpd_config.set_host(&host),
+                    AppEvent::SetMpdPort(port) => sbrc_mpd_config.port = Some(port),
+                    AppEvent::SetMpdPassword(password) =>
+                        sbrc_mpd_config.password = Some(password),
+                    AppEvent::ConnectMpd => if let Some(rx) = mpd_rx.take() {
+                        let config = self.mpd_config.clone()
+                            .or(sbrc_mpd_config.clone());
+
+                        mpd_sender::init_mpd_sender_thread(
+                            &config,
+                            rx,
+                            mpd_tx.clone(),
+                            tx.clone(),
+                        );
+                        mpd_listener::init_mpd_listener_thread(&config, tx.clone());
+                    },
                 },
-                Event::ToCommandLine(e) => command_line.handle(&e, tx.clone()),
+                Event::ToCommandLine(e) => command_line.handle(&e, &state, tx.clone()),
                 Event::ToScreen(e) => match e {
                     ScreenEvent::FocusNext => {
+                        let old = screen.focus(&components);
                         screen.focus_next(&mut components);
-                        tx.send(Event::ToApp(AppEvent::DrawScreen)).unwrap();
+                        screen.needs_redraw(&old, &components);
+                        screen.needs_redraw(&screen.focus(&components), &components);
                     },
                     ScreenEvent::FocusPrev => {
+                        let old = screen.focus(&components);
                         screen.focus_prev(&mut components);
-                        tx.send(Event::ToApp(AppEvent::DrawScreen)).unwrap();
-                    },
-                    ScreenEvent::NeedsRedraw(name) => {
-                        if screen.contains(&name, &components) {
-                            tx.send(
-                                Event::ToApp(AppEvent::DrawScreen)
-                            ).unwrap();
-                        }
+                        screen.needs_redraw(&old, &components);
+                        screen.needs_redraw(&screen.focus(&components), &components);
                     },
+                    ScreenEvent::NeedsRedraw(name) =>
+                        screen.needs_redraw(&name, &components),
                 },
                 Event::ToAllComponents(e) => {
                     for c in components.values_mut() {
@@ -249,22 +418,21 @@ impl<'a> Shellbird<'a> {
         });
     }
 
-    fn run_sbrc(&self, tx: mpsc::Sender<Event>) {
+    fn init_tick_thread(&self, tx: mpsc::Sender<Event>) {
+        thread::spawn(move || {
+            loop {
+                thread::sleep(Duration::from_secs(1));
+                tx.send(Event::ToApp(AppEvent::Tick)).unwrap();
+            }
+        });
+    }
+
+    fn run_sbrc(&self, command_line: &mut CommandLine, tx: mpsc::Sender<Event>) {
         if let Some(path) = &self.sbrc_path {
             let sbrc = File::open(path).unwrap();
             let reader = BufReader::new(sbrc);
 
-            for (i, line) in reader.lines().enumerate() {
-                let line = line.unwrap();
-                match command_line::run_headless(&line, tx.clone()) {
-                    Ok(_) => (),
-                    _ => tx.send(
-                        Event::ToCommandLine(
-                            CommandLineEvent::SbrcError(i + 1, line.to_string())
-                        )
-                    ).unwrap(),
-                }
-            }
+            command_line.source(reader.lines().map(|line| line.unwrap()));
         } else {
             tx.send(
                 Event::ToCommandLine(CommandLineEvent::SbrcNotFound)
@@ -273,18 +441,3 @@ impl<'a> Shellbird<'a> {
     }
 
 }
-fn spawn_draw_screen_event(
-    screen: &Screen,
-    components: &HashMap<String, Components>,
-) -> Event {
-    let (w, h) = termion::terminal_size().unwrap();
-    let h = h - 1;
-    if let Some(_) = components.get(screen.name()) {
-        Event::ToComponent(
-            screen.to_string(),
-            ComponentEvent::Draw(1, 1, w, h, screen.focus(components)),
-        )
-    } else {
-        Event::ToApp(AppEvent::ClearScreen)
-    }
-}
@@ -0,0 +1,116 @@
+/* Indexed store of every track in mpd's database
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+use mpd::Song;
+
+#[cfg(test)]
+mod tests;
+
+/* Index of a song in the library. Ids stay valid until the library is
+ * reloaded */
+pub type SongId = usize;
+
+/* Songs are only ever stored here. Everything else refers to them by id */
+#[derive(Clone)]
+#[derive(Default)]
+pub struct Library {
+    songs: Vec<Song>,
+    // Ids of songs by tag, then by value of that tag
+    tags: HashMap<String, HashMap<String, Vec<SongId>>>,
+    files: BTreeMap<String, SongId>,
+}
+
+impl Library {
+    pub fn new() -> Library {
+        Library::default()
+    }
+
+    /* Adds songs on the end of the library, indexing them as they go */
+    pub fn add(&mut self, songs: Vec<Song>) {
+        for song in songs {
+            let id = self.songs.len();
+
+            for (tag, value) in song.tags.iter() {
+                self.tags.entry(tag.to_string()).or_default()
+                    .entry(value.to_string()).or_default()
+                    .push(id);
+            }
+
+            self.files.insert(song.file.clone(), id);
+            self.songs.push(song);
+        }
+    }
+
+    pub fn get(&self, id: SongId) -> Option<&Song> {
+        self.songs.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.songs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.songs.is_empty()
+    }
+
+    /* Every id in the library, in order */
+    pub fn ids(&self) -> Range<SongId> {
+        0..self.songs.len()
+    }
+
+    /* Songs whose tag is exactly value, in order */
+    pub fn with_tag(&self, tag: &str, value: &str) -> &[SongId] {
+        match self.tags.get(tag).and_then(|values| values.get(value)) {
+            Some(ids) => ids,
+            None => &[],
+        }
+    }
+
+    /* Every value tag has in the library, in no particular order */
+    pub fn values(&self, tag: &str) -> Vec<&str> {
+        match self.tags.get(tag) {
+            Some(values) => values.keys().map(|s| s.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn find_file(&self, file: &str) -> Option<SongId> {
+        self.files.get(file).copied()
+    }
+
+    /* Songs anywhere under dir, in order. An empty dir is the whole library */
+    pub fn under(&self, dir: &str) -> Vec<SongId> {
+        if dir.is_empty() {
+            return self.ids().collect();
+        }
+
+        let prefix = format!("{}/", dir);
+
+        let mut ret: Vec<SongId> = self.files.range(prefix.clone()..)
+            .take_while(|(file, _)| file.starts_with(&prefix))
+            .map(|(_, id)| *id)
+            .collect();
+
+        ret.sort_unstable();
+        ret
+    }
+}
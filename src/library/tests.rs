@@ -0,0 +1,57 @@
+/* Tests for the library
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+#[test]
+fn test_indexes() -> Result<(), String> {
+    let mut library = Library::new();
+
+    library.add(vec![
+        song("b/2.flac", "Jazz"),
+        song("a/1.flac", "Rock"),
+    ]);
+    library.add(vec![
+        song("a/sub/3.flac", "Jazz"),
+        song("ab/4.flac", "Jazz"),
+    ]);
+
+    assert_eq!(library.len(), 4);
+    assert_eq!(library.with_tag("Genre", "Jazz"), &[0, 2, 3]);
+    assert_eq!(library.with_tag("Genre", "Pop"), &[] as &[SongId]);
+    assert_eq!(library.with_tag("Artist", "Jazz"), &[] as &[SongId]);
+    assert_eq!(library.find_file("a/sub/3.flac"), Some(2));
+    assert_eq!(library.find_file("c/5.flac"), None);
+    assert_eq!(library.under("a"), vec![1, 2]);
+    assert_eq!(library.under("a/sub"), vec![2]);
+    assert_eq!(library.under(""), vec![0, 1, 2, 3]);
+
+    Ok(())
+}
+
+fn song(file: &str, genre: &str) -> Song {
+    let mut ret = Song {
+        file: file.to_string(),
+        ..Song::default()
+    };
+
+    ret.tags.insert("Genre".to_string(), genre.to_string());
+
+    ret
+}
@@ -23,6 +23,7 @@ use std::io;
 use std::path::Path;
 
 use shellbird::Shellbird;
+use shellbird::music::MpdConfig;
 
 use termion::raw::IntoRawMode;
 
@@ -37,6 +38,15 @@ struct Opts {
     layout: Option<String>,
     #[clap(short)]
     debug: bool,
+    /// Mpd host, password@host, or path to mpd's unix socket
+    #[clap(long)]
+    host: Option<String>,
+    /// Mpd port
+    #[clap(long)]
+    port: Option<String>,
+    /// Mpd password
+    #[clap(long)]
+    password: Option<String>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -45,6 +55,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let sbrc_path = get_sbrc(opts.sbrc);
     let layout_path = get_layout_path(opts.layout);
     let genres_path = get_genre_path(opts.genres);
+    let history_path = get_history_path();
+    let mpd_config = get_mpd_config(opts.host, opts.port, opts.password);
 
     let stdout = io::stdout().into_raw_mode().unwrap();
 
@@ -52,14 +64,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         genres_path,
         sbrc_path,
         layout_path,
-        "127.0.0.1",
-        "6600",
+        history_path,
+        mpd_config,
         opts.debug,
     ).run(stdout)?;
 
     Ok(())
 }
 
+fn get_mpd_config(
+    host: Option<String>,
+    port: Option<String>,
+    password: Option<String>,
+) -> MpdConfig {
+    let mut cli = MpdConfig::new();
+
+    if let Some(host) = host {
+        cli.set_host(&host);
+    }
+
+    if port.is_some() {
+        cli.port = port;
+    }
+
+    if password.is_some() {
+        cli.password = password;
+    }
+
+    cli.or(MpdConfig::from_env())
+}
 
 fn get_sbrc(path_override: Option<String>) -> Option<String> {
     if let Some(path) = path_override {
@@ -159,3 +192,24 @@ fn get_genre_path(path_override: Option<String>) -> Option<String> {
 
     None
 }
+
+/* Unlike the other files, history doesn't have to exist yet. It goes in the
+ * config directory if there is one */
+fn get_history_path() -> Option<String> {
+    let mut home = home::home_dir()?;
+
+    let free_desktop = {
+        let mut home = home.clone();
+        home.push(".config/shellbird");
+        home
+    };
+
+    if free_desktop.as_path().is_dir() {
+        home = free_desktop;
+        home.push("history");
+    } else {
+        home.push(".sbhistory");
+    }
+
+    home.to_str().map(|s| s.to_string())
+}
@@ -0,0 +1,113 @@
+/* Functionality for establishing and keeping a connection to mpd
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::fmt;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::event::*;
+use super::{get_mpd_conn, get_raw_conn, ConnectError, MpdClient, MpdConfig, RawClient};
+
+const MIN_BACKOFF: u64 = 1;
+const MAX_BACKOFF: u64 = 32;
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Backoff(u64),
+    AuthFailed(u64),
+}
+
+impl fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectionState::Connecting => write!(f, "Connecting to mpd..."),
+            ConnectionState::Connected => write!(f, "Connected to mpd"),
+            ConnectionState::Backoff(secs) =>
+                write!(f, "No connection to mpd. Reconnecting in {}s", secs),
+            ConnectionState::AuthFailed(secs) =>
+                write!(f, "Mpd rejected password. Retrying in {}s", secs),
+        }
+    }
+}
+
+/* Connects to mpd, waiting between failed attempts with exponential
+ * backoff. If given a tx, every state change is published as an
+ * AppEvent::MpdConnection */
+pub struct Connector {
+    config: MpdConfig,
+    backoff: u64,
+    tx: Option<mpsc::Sender<Event>>,
+}
+
+impl Connector {
+    pub fn new(config: &MpdConfig, tx: Option<mpsc::Sender<Event>>) -> Connector {
+        Connector {
+            config: config.clone(),
+            backoff: MIN_BACKOFF,
+            tx,
+        }
+    }
+
+    pub fn connect(&mut self) -> MpdClient {
+        self.connect_with(get_mpd_conn)
+    }
+
+    /* Same as connect, but opens a RawClient alongside the MpdClient,
+     * retrying until both are up */
+    pub fn connect_both(&mut self) -> (MpdClient, RawClient) {
+        self.connect_with(|config| Ok((get_mpd_conn(config)?, get_raw_conn(config)?)))
+    }
+
+    fn connect_with<T>(&mut self, open: fn(&MpdConfig) -> Result<T, ConnectError>) -> T {
+        self.publish(ConnectionState::Connecting);
+
+        loop {
+            match open(&self.config) {
+                Ok(conn) => {
+                    self.backoff = MIN_BACKOFF;
+                    self.publish(ConnectionState::Connected);
+                    return conn
+                },
+                Err(ConnectError::AuthFailed) => {
+                    self.publish(ConnectionState::AuthFailed(MAX_BACKOFF));
+                    thread::sleep(Duration::from_secs(MAX_BACKOFF));
+                },
+                Err(ConnectError::Unreachable) => {
+                    self.publish(ConnectionState::Backoff(self.backoff));
+                    thread::sleep(Duration::from_secs(self.backoff));
+                    self.backoff = std::cmp::min(self.backoff * 2, MAX_BACKOFF);
+                },
+            }
+
+            self.publish(ConnectionState::Connecting);
+        }
+    }
+
+    fn publish(&self, state: ConnectionState) {
+        if let Some(tx) = &self.tx {
+            tx.send(Event::ToApp(AppEvent::MpdConnection(state))).unwrap();
+        }
+    }
+}
@@ -0,0 +1,543 @@
+/* A fake mpd for testing the threads which talk to mpd
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mpd::Song;
+
+use super::MpdConfig;
+
+/* Everything the fake mpd knows about. Tests set this up before starting
+ * the server and can look at or change it while it runs */
+#[derive(Default)]
+pub struct MockState {
+    pub library: Vec<Song>,
+    pub queue: Vec<Song>,
+    pub playlists: BTreeMap<String, Vec<Song>>,
+    // Position in the queue of the current track
+    pub current: Option<usize>,
+    pub playing: bool,
+    pub volume: i8,
+    pub random: bool,
+    pub repeat: bool,
+    pub single: bool,
+    pub consume: bool,
+    pub password: Option<String>,
+    // A command, with its arguments unquoted, to hang up on instead of
+    // answering. Only the first one received is hung up on
+    pub hang_up_on: Option<String>,
+    // Every command received, in order
+    pub log: Vec<String>,
+    connections: Vec<TcpStream>,
+    idlers: Vec<mpsc::Sender<Vec<String>>>,
+}
+
+impl MockState {
+    pub fn new() -> MockState {
+        MockState {
+            volume: 50,
+            ..MockState::default()
+        }
+    }
+}
+
+/* Speaks the mpd protocol on a local port, answering from a MockState */
+pub struct MockMpd {
+    port: u16,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockMpd {
+    pub fn start(state: MockState) -> MockMpd {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(state));
+
+        let shared = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    _ => continue,
+                };
+
+                if let Ok(clone) = stream.try_clone() {
+                    shared.lock().unwrap().connections.push(clone);
+                }
+
+                let state = shared.clone();
+                thread::spawn(move || serve(stream, state));
+            }
+        });
+
+        MockMpd { port, state }
+    }
+
+    pub fn config(&self) -> MpdConfig {
+        MpdConfig {
+            host: Some("127.0.0.1".to_string()),
+            port: Some(self.port.to_string()),
+            password: self.state().password.clone(),
+        }
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /* Waits for cond to hold, giving up after a few seconds */
+    pub fn wait_for<F>(&self, cond: F) -> Result<(), String>
+        where F: Fn(&MockState) -> bool
+    {
+        let start = Instant::now();
+
+        while start.elapsed() < Duration::from_secs(5) {
+            if cond(&self.state()) {
+                return Ok(());
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        Err("Timed out waiting on mock mpd".to_string())
+    }
+
+    /* Waits for a client to be idling, so it will see the next notify */
+    pub fn wait_for_idle(&self) -> Result<(), String> {
+        self.wait_for(|state| !state.idlers.is_empty())
+    }
+
+    /* Tells idling clients that subsystems have changed */
+    pub fn notify(&self, subsystems: &[&str]) {
+        let changed: Vec<String> = subsystems.iter().map(|s| s.to_string()).collect();
+
+        for idler in self.state().idlers.drain(..) {
+            let _ = idler.send(changed.clone());
+        }
+    }
+
+    /* How many connections clients have opened since the last
+     * drop_connections */
+    pub fn connections(&self) -> usize {
+        self.state().connections.len()
+    }
+
+    /* Hangs up on every client, like mpd being restarted */
+    pub fn drop_connections(&self) {
+        let mut state = self.state();
+
+        state.idlers.clear();
+
+        for conn in state.connections.drain(..) {
+            let _ = conn.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+fn serve(stream: TcpStream, state: Arc<Mutex<MockState>>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        _ => return,
+    };
+
+    if writer.write_all(b"OK MPD 0.23.5\n").is_err() {
+        return;
+    }
+
+    let mut list: Option<Vec<Vec<String>>> = None;
+
+    for line in BufReader::new(stream).lines() {
+        let line = match line {
+            Ok(line) => line,
+            _ => return,
+        };
+
+        let args = tokenize(&line);
+
+        {
+            let mut state = state.lock().unwrap();
+            state.log.push(line.clone());
+
+            if state.hang_up_on.as_ref() == Some(&args.join(" ")) {
+                state.hang_up_on = None;
+                let _ = writer.shutdown(Shutdown::Both);
+                return;
+            }
+        }
+
+        let reply = match (args.first().map(|s| s.as_str()), &mut list) {
+            (None, _) => continue,
+            (Some("command_list_begin"), _) => {
+                list = Some(Vec::new());
+                continue;
+            },
+            (Some("command_list_end"), _) => {
+                let cmds = list.take().unwrap_or_default();
+                let mut state = state.lock().unwrap();
+
+                cmds.iter()
+                    .map(|cmd| respond(&mut state, cmd))
+                    .collect::<Result<String, String>>()
+                    .map(|body| body + "OK\n")
+                    .unwrap_or_else(|ack| ack)
+            },
+            (Some(_), Some(cmds)) => {
+                cmds.push(args);
+                continue;
+            },
+            (Some("idle"), None) => {
+                let (tx, rx) = mpsc::channel();
+                state.lock().unwrap().idlers.push(tx);
+
+                match rx.recv() {
+                    Ok(changed) => changed.iter()
+                        .map(|s| format!("changed: {}\n", s))
+                        .collect::<String>() + "OK\n",
+                    _ => return,
+                }
+            },
+            (Some(_), None) => match respond(&mut state.lock().unwrap(), &args) {
+                Ok(body) => body + "OK\n",
+                Err(ack) => ack,
+            },
+        };
+
+        if writer.write_all(reply.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/* Runs a single command, returning the lines to send before OK, or the ACK
+ * to send instead */
+fn respond(state: &mut MockState, args: &[String]) -> Result<String, String> {
+    let cmd = args[0].as_str();
+    let arg = |i: usize| args.get(i).map(|s| s.as_str()).unwrap_or_default();
+    let ack = |code: u8, msg: &str| format!("ACK [{}@0] {{{}}} {}\n", code, cmd, msg);
+    let flag = |i: usize| arg(i) == "1";
+
+    match cmd {
+        "password" => match &state.password {
+            Some(password) if password == arg(1) => Ok(String::new()),
+            _ => Err(ack(3, "incorrect password")),
+        },
+        "ping" => Ok(String::new()),
+        "stats" => Ok(format!("songs: {}\n", state.library.len())),
+        "status" => {
+            let mut ret = format!(
+                "volume: {}\nrepeat: {}\nrandom: {}\nsingle: {}\nconsume: {}\n\
+                    playlistlength: {}\nstate: {}\n",
+                state.volume,
+                state.repeat as u8,
+                state.random as u8,
+                state.single as u8,
+                state.consume as u8,
+                state.queue.len(),
+                match (state.current, state.playing) {
+                    (Some(_), true) => "play",
+                    (Some(_), false) => "pause",
+                    (None, _) => "stop",
+                },
+            );
+
+            if let Some(pos) = state.current {
+                ret.push_str(&format!("song: {}\nsongid: {}\n", pos, pos + 1));
+            }
+
+            Ok(ret)
+        },
+        "replay_gain_status" => Ok("replay_gain_mode: off\n".to_string()),
+        "currentsong" => Ok(match state.current.and_then(|i| state.queue.get(i)) {
+            Some(song) => song_lines(song, state.current),
+            None => String::new(),
+        }),
+        "playlistinfo" => Ok(state.queue.iter().enumerate()
+            .map(|(i, song)| song_lines(song, Some(i)))
+            .collect()),
+        "listplaylists" => Ok(state.playlists.keys()
+            .map(|name| format!("playlist: {}\nLast-Modified: 2021-01-01T00:00:00Z\n", name))
+            .collect()),
+        "listplaylistinfo" => match state.playlists.get(arg(1)) {
+            Some(tracks) => Ok(tracks.iter().map(|song| song_lines(song, None)).collect()),
+            None => Err(ack(50, "No such playlist")),
+        },
+        "find" | "search" => {
+            let (filters, window) = match args.iter().position(|a| a == "window") {
+                Some(i) => (&args[1..i], args.get(i + 1).map(|w| w.as_str())),
+                None => (&args[1..], None),
+            };
+
+            let found: Vec<&Song> = state.library.iter()
+                .filter(|song| filters.chunks(2)
+                    .all(|f| matches(song, &f[0], f.get(1).map_or("", |s| s.as_str()), cmd == "find")))
+                .collect();
+
+            let (start, end) = window.map_or((0, found.len()), |w| range(w, found.len()));
+
+            Ok(found.iter()
+                .skip(start)
+                .take(end.saturating_sub(start))
+                .map(|song| song_lines(song, None))
+                .collect())
+        },
+        "lsinfo" => {
+            let dir = arg(1);
+            let prefix = match dir {
+                "" => String::new(),
+                dir => format!("{}/", dir),
+            };
+
+            let mut dirs = BTreeSet::new();
+            let mut files = Vec::new();
+
+            for song in &state.library {
+                if let Some(rest) = song.file.strip_prefix(&prefix) {
+                    match rest.split_once('/') {
+                        Some((sub, _)) => { dirs.insert(format!("{}{}", prefix, sub)); },
+                        None => files.push(song.file.clone()),
+                    }
+                }
+            }
+
+            Ok(dirs.iter().map(|d| format!("directory: {}\n", d))
+                .chain(files.iter().map(|f| format!("file: {}\n", f)))
+                .collect())
+        },
+        "add" => {
+            let path = arg(1);
+            let songs: Vec<Song> = state.library.iter()
+                .filter(|song| song.file == path || song.file.starts_with(&format!("{}/", path)))
+                .cloned()
+                .collect();
+
+            match songs.is_empty() {
+                true => Err(ack(50, "No such directory")),
+                false => {
+                    state.queue.extend(songs);
+                    Ok(String::new())
+                },
+            }
+        },
+        "addid" => {
+            let mut song = Song::default();
+            song.file = arg(1).to_string();
+
+            let song = state.library.iter()
+                .find(|s| s.file == song.file)
+                .cloned()
+                .unwrap_or(song);
+
+            match arg(2).parse::<usize>() {
+                Ok(pos) if pos <= state.queue.len() => {
+                    state.queue.insert(pos, song);
+                    Ok(format!("Id: {}\n", pos + 1))
+                },
+                Ok(_) => Err(ack(2, "Bad song index")),
+                Err(_) => {
+                    state.queue.push(song);
+                    Ok(format!("Id: {}\n", state.queue.len()))
+                },
+            }
+        },
+        "delete" => {
+            let (start, end) = range(arg(1), state.queue.len());
+
+            match start < end && end <= state.queue.len() {
+                true => {
+                    state.queue.drain(start..end);
+                    Ok(String::new())
+                },
+                false => Err(ack(2, "Bad song index")),
+            }
+        },
+        "move" => match shift(&mut state.queue, arg(1), arg(2)) {
+            true => Ok(String::new()),
+            false => Err(ack(2, "Bad song index")),
+        },
+        "clear" => {
+            state.queue.clear();
+            state.current = None;
+            state.playing = false;
+            Ok(String::new())
+        },
+        "play" => match state.queue.is_empty() {
+            true => Ok(String::new()),
+            false => {
+                state.current = Some(arg(1).parse().unwrap_or(0));
+                state.playing = true;
+                Ok(String::new())
+            },
+        },
+        "pause" => {
+            state.playing = !state.playing && state.current.is_some();
+            Ok(String::new())
+        },
+        "stop" => {
+            state.playing = false;
+            Ok(String::new())
+        },
+        "setvol" => {
+            state.volume = arg(1).parse().map_err(|_| ack(2, "Bad volume"))?;
+            Ok(String::new())
+        },
+        "random" => { state.random = flag(1); Ok(String::new()) },
+        "repeat" => { state.repeat = flag(1); Ok(String::new()) },
+        "single" => { state.single = flag(1); Ok(String::new()) },
+        "consume" => { state.consume = flag(1); Ok(String::new()) },
+        "save" => {
+            let queue = state.queue.clone();
+            state.playlists.insert(arg(1).to_string(), queue);
+            Ok(String::new())
+        },
+        "rename" => match state.playlists.remove(arg(1)) {
+            Some(tracks) => {
+                state.playlists.insert(arg(2).to_string(), tracks);
+                Ok(String::new())
+            },
+            None => Err(ack(50, "No such playlist")),
+        },
+        "playlistdelete" => match state.playlists.get_mut(arg(1)) {
+            Some(tracks) => match arg(2).parse::<usize>() {
+                Ok(pos) if pos < tracks.len() => {
+                    tracks.remove(pos);
+                    Ok(String::new())
+                },
+                _ => Err(ack(2, "Bad song index")),
+            },
+            None => Err(ack(50, "No such playlist")),
+        },
+        "playlistmove" => match state.playlists.get_mut(arg(1)) {
+            Some(tracks) => match shift(tracks, arg(2), arg(3)) {
+                true => Ok(String::new()),
+                false => Err(ack(2, "Bad song index")),
+            },
+            None => Err(ack(50, "No such playlist")),
+        },
+        "rm" => match state.playlists.remove(arg(1)) {
+            Some(_) => Ok(String::new()),
+            None => Err(ack(50, "No such playlist")),
+        },
+        _ => Err(ack(5, &format!("unknown command \"{}\"", cmd))),
+    }
+}
+
+/* Whether a filter of term and value matches song. Find matches whole
+ * values, and search matches part of a value ignoring case, like mpd */
+fn matches(song: &Song, term: &str, value: &str, exact: bool) -> bool {
+    let lower = value.to_lowercase();
+    let has = |s: &str| match exact {
+        true => s == value,
+        false => s.to_lowercase().contains(&lower),
+    };
+
+    match term {
+        "base" => song.file.starts_with(&format!("{}/", value)),
+        "any" => has(&song.file)
+            || song.title.as_deref().map(has).unwrap_or(false)
+            || song.tags.values().any(|v| has(v)),
+        "file" => has(&song.file),
+        "title" => song.title.as_deref().map(has).unwrap_or(false),
+        tag => song.tags.iter()
+            .any(|(k, v)| k.eq_ignore_ascii_case(tag) && has(v)),
+    }
+}
+
+/* Moves the song at from to to, for move and playlistmove. Returns
+ * whether both were in range */
+fn shift(songs: &mut Vec<Song>, from: &str, to: &str) -> bool {
+    match (from.parse::<usize>(), to.parse::<usize>()) {
+        (Ok(from), Ok(to)) if from < songs.len() && to < songs.len() => {
+            let song = songs.remove(from);
+            songs.insert(to, song);
+            true
+        },
+        _ => false,
+    }
+}
+
+/* A position "n" or range "start:end", where end may be left off */
+fn range(arg: &str, len: usize) -> (usize, usize) {
+    match arg.split_once(':') {
+        Some((start, end)) => (
+            start.parse().unwrap_or(0),
+            end.parse().unwrap_or(len),
+        ),
+        None => {
+            let pos = arg.parse().unwrap_or(len);
+            (pos, pos + 1)
+        },
+    }
+}
+
+fn song_lines(song: &Song, pos: Option<usize>) -> String {
+    let mut ret = format!("file: {}\n", song.file);
+
+    if let Some(title) = &song.title {
+        ret.push_str(&format!("Title: {}\n", title));
+    }
+
+    for (k, v) in &song.tags {
+        ret.push_str(&format!("{}: {}\n", k, v));
+    }
+
+    if let Some(pos) = pos {
+        ret.push_str(&format!("Pos: {}\nId: {}\n", pos, pos + 1));
+    }
+
+    ret
+}
+
+/* Splits a command line into its words, undoing quoting */
+fn tokenize(line: &str) -> Vec<String> {
+    let mut ret = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+
+        let mut word = String::new();
+
+        match chars.peek() {
+            None => return ret,
+            Some('"') => {
+                chars.next();
+
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            },
+            Some(_) => while let Some(c) = chars.next_if(|c| *c != ' ') {
+                word.push(c);
+            },
+        }
+
+        ret.push(word);
+    }
+}
@@ -17,15 +17,193 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
+use std::env;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+
 use mpd::Client;
+use mpd::error::{Error, ErrorCode};
 
 pub mod mpd_sender;
 pub mod mpd_listener;
+mod connection;
+mod raw;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub use connection::{Connector, ConnectionState};
+pub use raw::{RawClient, DirEntry};
+
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_PORT: &str = "6600";
+
+/* Where and how to reach mpd. Any field left as None falls back to
+ * another config via or(), and finally to mpd's usual defaults.
+ *
+ * A host starting with '/' is treated as the path to a unix socket.
+ */
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct MpdConfig {
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub password: Option<String>,
+}
+
+impl MpdConfig {
+    pub fn new() -> MpdConfig {
+        MpdConfig {
+            host: None,
+            port: None,
+            password: None,
+        }
+    }
+
+    pub fn from_env() -> MpdConfig {
+        MpdConfig::from_vars(env::var("MPD_HOST").ok(), env::var("MPD_PORT").ok())
+    }
+
+    /* Config from the values of MPD_HOST and MPD_PORT */
+    pub fn from_vars(host: Option<String>, port: Option<String>) -> MpdConfig {
+        let mut ret = MpdConfig::new();
+
+        if let Some(host) = host {
+            ret.set_host(&host);
+        }
+
+        ret.port = port;
+        ret
+    }
+
+    /* Accepts mpd's MPD_HOST conventions: "host", "password@host" or
+     * "password@/path/to/socket" */
+    pub fn set_host(&mut self, host: &str) {
+        match host.rfind('@') {
+            Some(i) if i > 0 => {
+                self.password = Some(host[..i].to_string());
+                self.host = Some(host[i + 1..].to_string());
+            },
+            _ => self.host = Some(host.to_string()),
+        }
+    }
+
+    /* Fills in whatever self doesn't set from other. other's password is
+     * only used if it isn't for some other host than the one self sets */
+    pub fn or(self, other: MpdConfig) -> MpdConfig {
+        let password = match (&self.host, &other.host) {
+            (Some(host), Some(other_host)) if host != other_host => self.password,
+            _ => self.password.or(other.password),
+        };
+
+        MpdConfig {
+            host: self.host.or(other.host),
+            port: self.port.or(other.port),
+            password,
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        self.host.as_deref().unwrap_or(DEFAULT_HOST)
+    }
+
+    pub fn port(&self) -> &str {
+        self.port.as_deref().unwrap_or(DEFAULT_PORT)
+    }
+
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
 
-fn get_mpd_conn(ip: &str, port: &str) -> Option<Client> {
-    match Client::connect(format!("{}:{}", ip, port)) {
-        Ok(conn) => Some(conn),
-        _ => None,
+    pub fn is_socket(&self) -> bool {
+        self.host().starts_with('/')
     }
 }
 
+impl Default for MpdConfig {
+    fn default() -> MpdConfig {
+        MpdConfig::new()
+    }
+}
+
+#[derive(Debug)]
+pub enum MpdStream {
+    Tcp(TcpStream),
+    Unix(UnixStream),
+}
+
+impl Read for MpdStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            MpdStream::Tcp(s) => s.read(buf),
+            MpdStream::Unix(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for MpdStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            MpdStream::Tcp(s) => s.write(buf),
+            MpdStream::Unix(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            MpdStream::Tcp(s) => s.flush(),
+            MpdStream::Unix(s) => s.flush(),
+        }
+    }
+}
+
+pub type MpdClient = Client<MpdStream>;
+
+enum ConnectError {
+    Unreachable,
+    AuthFailed,
+}
+
+fn open_stream(config: &MpdConfig) -> io::Result<MpdStream> {
+    if config.is_socket() {
+        UnixStream::connect(config.host()).map(MpdStream::Unix)
+    } else {
+        TcpStream::connect(format!("{}:{}", config.host(), config.port()))
+            .map(MpdStream::Tcp)
+    }
+}
+
+fn get_mpd_conn(config: &MpdConfig) -> Result<MpdClient, ConnectError> {
+    let mut conn = match open_stream(config) {
+        Ok(stream) => match Client::new(stream) {
+            Ok(conn) => conn,
+            _ => return Err(ConnectError::Unreachable),
+        },
+        _ => return Err(ConnectError::Unreachable),
+    };
+
+    if let Some(password) = config.password() {
+        match conn.login(password) {
+            Ok(_) => (),
+            Err(Error::Server(e)) if e.code == ErrorCode::Password =>
+                return Err(ConnectError::AuthFailed),
+            Err(_) => return Err(ConnectError::Unreachable),
+        }
+    }
+
+    Ok(conn)
+}
+
+fn get_raw_conn(config: &MpdConfig) -> Result<RawClient, ConnectError> {
+    match RawClient::connect(config) {
+        Ok(conn) => Ok(conn),
+        Err(Error::Server(e)) if e.code == ErrorCode::Password =>
+            Err(ConnectError::AuthFailed),
+        Err(_) => Err(ConnectError::Unreachable),
+    }
+}
@@ -21,40 +21,47 @@ use std::sync::mpsc;
 use std::thread;
 
 use mpd::idle::{Idle, Subsystem};
-use mpd::{Query, Client, Term};
+use mpd::{Query, Term};
 
 use super::*;
 use crate::event::*;
 use crate::playlist::Playlist;
 
-pub fn init_mpd_listener_thread(ip: &str, port: &str, tx: mpsc::Sender<Event>) {
-    let ip = ip.to_string();
-    let port = port.to_string();
+const DATABASE_CHUNK: u32 = 1000;
+
+pub fn init_mpd_listener_thread(config: &MpdConfig, tx: mpsc::Sender<Event>) {
+    let config = config.clone();
 
     thread::spawn(move || {
+        let mut connector = Connector::new(&config, Some(tx.clone()));
         let mut conn = None;
 
         loop {
-            while let None = conn {
-                conn = get_mpd_conn(&ip, &port);
+            if conn.is_none() {
+                conn = Some(connector.connect());
             }
 
-            tx.send(spawn_error_msg("Mpd Connection established!")).unwrap();
-
             if let Some(c) = &mut conn {
                 send_database(c, &tx);
                 send_queue(c, &tx);
                 send_now_playing(c, &tx);
+                send_status(c, &tx);
                 send_playlists(c, &tx);
 
                 loop {
                     if let Ok(systems) = c.wait(&[]) {
                         for system in systems {
                             match system {
-                                Subsystem::Player => send_now_playing(c, &tx),
+                                Subsystem::Player => {
+                                    send_now_playing(c, &tx);
+                                    send_status(c, &tx);
+                                },
+                                Subsystem::Mixer
+                                | Subsystem::Options => send_status(c, &tx),
                                 Subsystem::Queue => send_queue(c, &tx),
                                 Subsystem::Playlist => send_playlists(c, &tx),
-                                Subsystem::Database => send_database(c, &tx),
+                                Subsystem::Database =>
+                                    send_database(c, &tx),
                                 _ => (),
                             }
                         }
@@ -63,11 +70,6 @@ pub fn init_mpd_listener_thread(ip: &str, port: &str, tx: mpsc::Sender<Event>) {
                             Event::ToApp(AppEvent::LostMpdConnection)
                         ).unwrap();
 
-                        tx.send(spawn_error_msg(
-                            "Mpd Connection dropped. Reestablishing \
-                                connection..."
-                        )).unwrap();
-
                         conn = None;
                         break;
                     }
@@ -77,25 +79,22 @@ pub fn init_mpd_listener_thread(ip: &str, port: &str, tx: mpsc::Sender<Event>) {
     });
 }
 
-fn spawn_error_msg(msg: &str) -> Event {
-    let msg = format!("Mpd Listener Thread: {}", msg);
-    Event::ToApp(AppEvent::Error(msg))
-}
-
-fn send_now_playing(conn: &mut Client, tx: &mpsc::Sender<Event>) {
+fn send_now_playing(conn: &mut MpdClient, tx: &mpsc::Sender<Event>) {
     match conn.currentsong() {
         Ok(song) =>
-            tx.send(
-                Event::ToAllComponents(ComponentEvent::NowPlaying(song))
-            ).unwrap(),
+            tx.send(Event::ToApp(AppEvent::NowPlaying(song))).unwrap(),
         _ =>
-            tx.send(
-                Event::ToAllComponents(ComponentEvent::NowPlaying(None))
-            ).unwrap(),
+            tx.send(Event::ToApp(AppEvent::NowPlaying(None))).unwrap(),
+    }
+}
+
+fn send_status(conn: &mut MpdClient, tx: &mpsc::Sender<Event>) {
+    if let Ok(status) = conn.status() {
+        tx.send(Event::ToApp(AppEvent::Status(status))).unwrap();
     }
 }
 
-fn send_queue(conn: &mut Client, tx: &mpsc::Sender<Event>) {
+fn send_queue(conn: &mut MpdClient, tx: &mpsc::Sender<Event>) {
     match conn.queue() {
         Ok(q) =>
             tx.send(Event::ToAllComponents(ComponentEvent::Queue(q))).unwrap(),
@@ -106,11 +105,11 @@ fn send_queue(conn: &mut Client, tx: &mpsc::Sender<Event>) {
     }
 }
 
-fn send_playlists(conn: &mut Client, tx: &mpsc::Sender<Event>) {
+fn send_playlists(conn: &mut MpdClient, tx: &mpsc::Sender<Event>) {
     match conn.playlists() {
         Ok(pl) =>
             tx.send(
-                Event::ToAllComponents(ComponentEvent::Playlist(
+                Event::ToApp(AppEvent::Playlist(
                     pl.iter()
                         .map(|pl| Playlist {
                             name: pl.name.clone(),
@@ -123,20 +122,50 @@ fn send_playlists(conn: &mut Client, tx: &mpsc::Sender<Event>) {
             ).unwrap(),
         _ =>
             tx.send(
-                Event::ToAllComponents(ComponentEvent::Playlist(Vec::new()))
+                Event::ToApp(AppEvent::Playlist(Vec::new()))
             ).unwrap(),
     }
 }
 
-fn send_database(conn: &mut Client, tx: &mpsc::Sender<Event>) {
-    let results = conn.search(
-        Query::new().and(Term::Any, ""),
-        None,
-    );
+/* Sends the library DATABASE_CHUNK tracks at a time, so that no one
+ * response from mpd is the whole of a large library. If the connection
+ * drops part way, the load is left unfinished for the reconnect to redo */
+fn send_database(conn: &mut MpdClient, tx: &mpsc::Sender<Event>) {
+    let total = match conn.stats() {
+        Ok(stats) => stats.songs,
+        _ => return,
+    };
 
-    match results {
-        Ok(results) =>
-            tx.send(Event::ToApp(AppEvent::Database(results))).unwrap(),
-        _ => (),
+    tx.send(Event::ToApp(AppEvent::DatabaseLoading(total))).unwrap();
+
+    let mut query = Query::new();
+    let everything = query.and(Term::Any, "");
+
+    let mut start = 0;
+
+    loop {
+        match conn.search(everything, (start, start + DATABASE_CHUNK)) {
+            Ok(songs) => {
+                let done = (songs.len() as u32) < DATABASE_CHUNK;
+
+                if !songs.is_empty() {
+                    tx.send(Event::ToApp(AppEvent::Database(songs))).unwrap();
+                }
+
+                // The mpd crate reads a dropped connection as the end of a
+                // response, so make sure a short part really is the last
+                if done {
+                    match conn.ping() {
+                        Ok(_) => break,
+                        Err(_) => return,
+                    }
+                }
+
+                start += DATABASE_CHUNK;
+            },
+            Err(_) => return,
+        }
     }
+
+    tx.send(Event::ToApp(AppEvent::DatabaseLoaded)).unwrap();
 }
@@ -19,36 +19,34 @@ along with Shellbird; see the file COPYING.  If not see
 
 use std::sync::mpsc;
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::thread;
 
 use crate::event::*;
+use super::{Connector, MpdClient, MpdConfig, RawClient};
 
 use mpd::Query;
 use mpd::Term;
-use mpd::Client;
 use mpd::error::Error;
-use mpd::Song;
 
 pub fn init_mpd_sender_thread(
-    ip: &str,
-    port: &str,
+    config: &MpdConfig,
+    rx: mpsc::Receiver<MpdEvent>,
+    rethrow_tx: mpsc::Sender<MpdEvent>,
     tx: mpsc::Sender<Event>
-) -> mpsc::Sender<MpdEvent> {
-    let (ret_tx, rx) = mpsc::channel();
+) {
+    let config = config.clone();
 
-    let ip = ip.to_string();
-    let port = port.to_string();
-
-    let rethrow_tx: mpsc::Sender<MpdEvent> = ret_tx.clone();
     thread::spawn(move || {
+        let mut connector = Connector::new(&config, Some(tx.clone()));
         let mut conn = None;
 
         loop {
-            while let None = conn {
-                conn = super::get_mpd_conn(&ip, &port);
+            if conn.is_none() {
+                conn = Some(connector.connect_both());
             }
 
-            if let Some(c) = &mut conn {
+            if let Some((c, raw)) = &mut conn {
                 let request = match rx.recv() {
                     Ok(command) => command,
                     _ => break, // Main program exited
@@ -57,15 +55,48 @@ pub fn init_mpd_sender_thread(
                 let result = match request.clone() {
                     MpdEvent::TogglePause => c.toggle_pause(),
                     MpdEvent::Random => toggle_random(c),
+                    MpdEvent::Repeat => toggle_repeat(c),
+                    MpdEvent::Single => toggle_single(c),
+                    MpdEvent::Consume => toggle_consume(c),
+                    MpdEvent::Crossfade(secs) => c.crossfade(secs),
+                    MpdEvent::Seek(a) => seek(c, a),
+                    MpdEvent::Volume(a) => volume(c, a),
+                    MpdEvent::Play => c.play(),
+                    MpdEvent::Stop => c.stop(),
                     MpdEvent::ClearQueue => c.clear(),
-                    MpdEvent::AddToQueue(songs) => push_all(c, songs),
-                    MpdEvent::PlayAt(song) => play_at(c, song),
-                    MpdEvent::AddStyleToQueue(genres) => add_style_to_queue(c, genres),
+                    MpdEvent::AddToQueue(files) => push_all(raw, files),
+                    MpdEvent::PlayPos(pos) => c.switch(pos),
+                    MpdEvent::PlayFile(file) => play_file(c, raw, file),
+                    MpdEvent::AddStyleToQueue(genres) =>
+                        add_style_to_queue(c, raw, genres),
                     MpdEvent::Next => c.next(),
                     MpdEvent::Prev => c.prev(),
+                    MpdEvent::Delete(positions) => delete(raw, positions),
+                    MpdEvent::MoveMany(moves) => move_many(raw, moves),
+                    MpdEvent::Shuffle(range) => c.shuffle(range),
+                    MpdEvent::Crop => crop(c, raw),
+                    MpdEvent::Dedup => dedup(c, raw),
+                    MpdEvent::SavePlaylist(name) => c.save(name),
+                    MpdEvent::RenamePlaylist(from, to) => c.pl_rename(from, &to),
+                    MpdEvent::RemovePlaylist(name) => c.pl_remove(name),
+                    MpdEvent::AddToPlaylist(name, files) =>
+                        pl_push_all(raw, &name, files),
+                    MpdEvent::PlaylistDelete(name, positions) =>
+                        pl_delete(raw, &name, positions),
+                    MpdEvent::PlaylistMoveMany(name, moves) =>
+                        pl_move_many(raw, &name, moves),
+                    MpdEvent::ListDirectory(name, path) =>
+                        list_directory(raw, name, path, &tx),
+                    MpdEvent::AddPaths(paths) => add_paths(raw, paths),
                 };
 
-                if let Err(_) = result {
+                if let Err(Error::Server(e)) = result {
+                    tx.send(Event::ToApp(AppEvent::Error(format!(
+                        "Mpd Sender Thread: Mpd rejected MpdRequest {:?}: {}",
+                        request,
+                        e.detail,
+                    )))).unwrap();
+                } else if result.is_err() {
                     tx.send(Event::ToApp(AppEvent::Error(format!(
                         "Mpd Sender Thread: Mpd Connection dropped. Resending \
                             MpdRequest {:?}",
@@ -77,11 +108,9 @@ pub fn init_mpd_sender_thread(
             }
         }
     });
-
-    ret_tx
 }
 
-fn toggle_random(conn: &mut Client) -> Result<(), Error> {
+fn toggle_random(conn: &mut MpdClient) -> Result<(), Error> {
     let stats = conn.status()?;
 
     conn.random(!stats.random)?;
@@ -89,42 +118,215 @@ fn toggle_random(conn: &mut Client) -> Result<(), Error> {
     Ok(())
 }
 
-fn push_all(conn: &mut Client, songs: Vec<Song>) -> Result<(), Error> {
-    for song in songs {
-        if let Err(e) = conn.push(song) {
-            return Err(e)
-        }
-    }
+fn toggle_repeat(conn: &mut MpdClient) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    conn.repeat(!stats.repeat)?;
+
+    Ok(())
+}
+
+fn toggle_single(conn: &mut MpdClient) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    conn.single(!stats.single)?;
 
     Ok(())
 }
 
-fn play_at(conn: &mut Client, song: Song) -> Result<(), Error> {
-    match song.place {
-        Some(place) => conn.switch(place.pos),
-        None => {
-            conn.push(song)?;
-            let q = conn.queue()?;
-            conn.switch(q.last().unwrap().place.unwrap().pos).unwrap();
+fn toggle_consume(conn: &mut MpdClient) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    conn.consume(!stats.consume)?;
 
-            Ok(())
+    Ok(())
+}
+
+fn seek(conn: &mut MpdClient, adjustment: Adjustment) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    // Nothing to seek in
+    if stats.song.is_none() {
+        return Ok(())
+    }
+
+    let pos = match adjustment {
+        Adjustment::To(secs) => secs,
+        Adjustment::By(secs) => match stats.elapsed {
+            Some(elapsed) => elapsed.num_seconds() + secs,
+            None => secs,
         },
+    };
+
+    conn.rewind(std::cmp::max(0, pos))
+}
+
+fn volume(conn: &mut MpdClient, adjustment: Adjustment) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    // Mpd reports -1 when there is no mixer to control
+    if stats.volume < 0 {
+        return Ok(())
+    }
+
+    let vol = match adjustment {
+        Adjustment::To(vol) => vol,
+        Adjustment::By(vol) => stats.volume as i64 + vol,
+    };
+
+    conn.volume(vol.clamp(0, 100) as i8)
+}
+
+/* Deletes from the back so earlier positions stay valid. The deletes go in
+ * one command list, since resending after only some of them ran would
+ * delete the wrong tracks */
+fn delete(conn: &mut RawClient, mut positions: Vec<u32>) -> Result<(), Error> {
+    positions.sort_unstable();
+    positions.dedup();
+
+    let cmds: Vec<_> = positions.into_iter().rev()
+        .map(|pos| ("delete", vec![pos.to_string()]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+/* Makes every move in order, in one command list so that a resend after a
+ * dropped connection doesn't make some of them twice */
+fn move_many(conn: &mut RawClient, moves: Vec<(u32, u32)>) -> Result<(), Error> {
+    let cmds: Vec<_> = moves.into_iter()
+        .map(|(from, to)| ("move", vec![from.to_string(), to.to_string()]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+/* Removes everything but the playing track */
+fn crop(conn: &mut MpdClient, raw: &mut RawClient) -> Result<(), Error> {
+    let stats = conn.status()?;
+
+    let pos = match stats.song {
+        Some(place) => place.pos,
+        None => return Ok(()),
+    };
+
+    let mut cmds = Vec::new();
+
+    if pos + 1 < stats.queue_len {
+        cmds.push(("delete", vec![format!("{}:{}", pos + 1, stats.queue_len)]));
     }
+
+    if pos > 0 {
+        cmds.push(("delete", vec![format!("0:{}", pos)]));
+    }
+
+    raw.run_list(&cmds)
+}
+
+/* Removes every track whose file is already further up the queue */
+fn dedup(conn: &mut MpdClient, raw: &mut RawClient) -> Result<(), Error> {
+    let mut seen = HashSet::new();
+
+    let dupes = conn.queue()?.into_iter()
+        .filter(|s| !seen.insert(s.file.clone()))
+        .filter_map(|s| s.place.map(|p| p.pos))
+        .collect();
+
+    delete(raw, dupes)
+}
+
+fn list_directory(
+    conn: &mut RawClient,
+    name: String,
+    path: String,
+    tx: &mpsc::Sender<Event>,
+) -> Result<(), Error> {
+    let entries = conn.lsinfo(&path)?;
+
+    tx.send(Event::ToComponent(
+        name,
+        ComponentEvent::Directory(path, entries),
+    )).unwrap();
+
+    Ok(())
+}
+
+fn add_paths(conn: &mut RawClient, paths: Vec<String>) -> Result<(), Error> {
+    let cmds: Vec<_> = paths.into_iter()
+        .map(|path| ("add", vec![path]))
+        .collect();
+
+    conn.run_list(&cmds)
 }
 
-fn add_style_to_queue(conn: &mut Client, genres: Vec<String>) -> Result<(), Error> {
+fn pl_push_all(conn: &mut RawClient, name: &str, files: Vec<String>) -> Result<(), Error> {
+    let cmds: Vec<_> = files.into_iter()
+        .map(|file| ("playlistadd", vec![name.to_string(), file]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+/* Deletes from the back so earlier positions stay valid, all in one command
+ * list like delete */
+fn pl_delete(conn: &mut RawClient, name: &str, mut positions: Vec<u32>) -> Result<(), Error> {
+    positions.sort_unstable();
+    positions.dedup();
+
+    let cmds: Vec<_> = positions.into_iter().rev()
+        .map(|pos| ("playlistdelete", vec![name.to_string(), pos.to_string()]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+fn pl_move_many(conn: &mut RawClient, name: &str, moves: Vec<(u32, u32)>) -> Result<(), Error> {
+    let cmds: Vec<_> = moves.into_iter()
+        .map(|(from, to)| ("playlistmove", vec![name.to_string(), from.to_string(), to.to_string()]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+/* Adds every file in one command list, so that a resend after a dropped
+ * connection doesn't add some of them twice */
+fn push_all(conn: &mut RawClient, files: Vec<String>) -> Result<(), Error> {
+    let cmds: Vec<_> = files.into_iter()
+        .map(|file| ("add", vec![file]))
+        .collect();
+
+    conn.run_list(&cmds)
+}
+
+/* Adds file to the end of the queue and plays it. Both go in one command
+ * list, so a resend can't add it twice, and if the queue changed length in
+ * the meantime mpd rejects the add rather than playing the wrong track */
+fn play_file(conn: &mut MpdClient, raw: &mut RawClient, file: String) -> Result<(), Error> {
+    let end = conn.status()?.queue_len.to_string();
+
+    raw.run_list(&[
+        ("addid", vec![file, end.clone()]),
+        ("play", vec![end]),
+    ])
+}
+
+fn add_style_to_queue(
+    conn: &mut MpdClient,
+    raw: &mut RawClient,
+    genres: Vec<String>,
+) -> Result<(), Error> {
+    let mut files = Vec::new();
+
     for genre in genres {
-        let songs = conn.search(
+        files.extend(conn.search(
             Query::new()
                 .and(
                     Term::Tag(Cow::Borrowed("Genre")),
                     genre
                 ),
                 None
-        )?;
-
-        push_all(conn, songs)?;
+        )?.into_iter().map(|song| song.file));
     }
 
-    Ok(())
+    push_all(raw, files)
 }
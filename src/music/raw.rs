@@ -0,0 +1,156 @@
+/* Bare access to the mpd protocol
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::io::{self, BufRead, BufReader, Write};
+
+use mpd::error::{Error, ProtoError};
+
+use super::{open_stream, MpdConfig, MpdStream};
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum DirEntry {
+    Directory(String),
+    File(String),
+}
+
+impl DirEntry {
+    pub fn path(&self) -> &str {
+        match self {
+            DirEntry::Directory(path) => path,
+            DirEntry::File(path) => path,
+        }
+    }
+}
+
+/* A connection for the commands the mpd crate doesn't have or gets wrong,
+ * like lsinfo on a directory, or add. The mpd crate keeps its socket to
+ * itself, so this is a separate connection, which the sender thread keeps
+ * open next to its MpdClient */
+pub struct RawClient {
+    stream: BufReader<MpdStream>,
+}
+
+impl RawClient {
+    pub fn connect(config: &MpdConfig) -> Result<RawClient, Error> {
+        let mut ret = RawClient {
+            stream: BufReader::new(open_stream(config)?),
+        };
+
+        if !ret.read_line()?.starts_with("OK MPD ") {
+            return Err(Error::Proto(ProtoError::BadBanner));
+        }
+
+        if let Some(password) = config.password() {
+            ret.run("password", &[password])?;
+        }
+
+        Ok(ret)
+    }
+
+    /* Runs cmd and returns the key value pairs mpd responds with */
+    pub fn run(&mut self, cmd: &str, args: &[&str]) -> Result<Vec<(String, String)>, Error> {
+        self.stream.get_mut().write_all(line(cmd, args).as_bytes())?;
+        self.read_response()
+    }
+
+    /* Runs every command in one command list. Mpd only starts on them once
+     * it has the whole list, so a connection dropped on the way runs none
+     * of them, rather than some */
+    pub fn run_list(&mut self, cmds: &[(&str, Vec<String>)]) -> Result<(), Error> {
+        let mut list = "command_list_begin\n".to_string();
+
+        for (cmd, args) in cmds {
+            let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            list.push_str(&line(cmd, &args));
+        }
+
+        list.push_str("command_list_end\n");
+
+        self.stream.get_mut().write_all(list.as_bytes())?;
+        self.read_response().map(|_| ())
+    }
+
+    /* Directories and files directly inside path. An empty path is the root
+     * of the music directory */
+    pub fn lsinfo(&mut self, path: &str) -> Result<Vec<DirEntry>, Error> {
+        let args = match path {
+            "" => Vec::new(),
+            path => vec![path],
+        };
+
+        Ok(self.run("lsinfo", &args)?.into_iter()
+            .filter_map(|(k, v)| match k.as_str() {
+                "directory" => Some(DirEntry::Directory(v)),
+                "file" => Some(DirEntry::File(v)),
+                _ => None,
+            }).collect())
+    }
+
+    /* Adds path to the queue. Directories are added recursively */
+    pub fn add(&mut self, path: &str) -> Result<(), Error> {
+        self.run("add", &[path]).map(|_| ())
+    }
+
+    fn read_response(&mut self) -> Result<Vec<(String, String)>, Error> {
+        let mut ret = Vec::new();
+
+        loop {
+            let line = self.read_line()?;
+
+            if line == "OK" {
+                return Ok(ret);
+            } else if line.starts_with("ACK ") {
+                return Err(Error::Server(line.parse()?));
+            }
+
+            match line.split_once(": ") {
+                Some((k, v)) => ret.push((k.to_string(), v.to_string())),
+                None => return Err(Error::Proto(ProtoError::NotPair)),
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String, Error> {
+        let mut line = String::new();
+
+        if self.stream.read_line(&mut line)? == 0 {
+            return Err(Error::Io(io::Error::from(io::ErrorKind::UnexpectedEof)));
+        }
+
+        Ok(line.trim_end_matches('\n').to_string())
+    }
+}
+
+fn line(cmd: &str, args: &[&str]) -> String {
+    let mut ret = cmd.to_string();
+
+    for arg in args {
+        ret.push(' ');
+        ret.push_str(&quote(arg));
+    }
+
+    ret.push('\n');
+    ret
+}
+
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
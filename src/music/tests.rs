@@ -0,0 +1,520 @@
+/* Tests for talking to mpd, against a fake mpd
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use mpd::Song;
+
+use crate::event::*;
+use crate::playlist::Playlist;
+use mock::{MockMpd, MockState};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn song(file: &str, title: &str) -> Song {
+    let mut song = Song::default();
+    song.file = file.to_string();
+    song.title = Some(title.to_string());
+    song.tags.insert("Artist".to_string(), "Band".to_string());
+    song
+}
+
+fn library(n: usize) -> Vec<Song> {
+    (0..n)
+        .map(|i| song(&format!("Band/Album/{}.flac", i), &format!("Song {}", i)))
+        .collect()
+}
+
+/* Receives until an event f accepts, skipping everything else */
+fn expect<F>(rx: &mpsc::Receiver<Event>, what: &str, f: F) -> Result<Event, String>
+    where F: Fn(&Event) -> bool
+{
+    let start = Instant::now();
+
+    while let Some(left) = TIMEOUT.checked_sub(start.elapsed()) {
+        match rx.recv_timeout(left) {
+            Ok(e) if f(&e) => return Ok(e),
+            Ok(_) => (),
+            Err(_) => break,
+        }
+    }
+
+    Err(format!("Timed out waiting for {}", what))
+}
+
+fn start_listener(mpd: &MockMpd) -> mpsc::Receiver<Event> {
+    let (tx, rx) = mpsc::channel();
+    mpd_listener::init_mpd_listener_thread(&mpd.config(), tx);
+    rx
+}
+
+fn start_sender(mpd: &MockMpd) -> (mpsc::Sender<MpdEvent>, mpsc::Receiver<Event>) {
+    let (tx, rx) = mpsc::channel();
+    let (mpd_tx, mpd_rx) = mpsc::channel();
+    mpd_sender::init_mpd_sender_thread(&mpd.config(), mpd_rx, mpd_tx.clone(), tx);
+    (mpd_tx, rx)
+}
+
+#[test]
+fn test_listener_sends_everything_on_connect() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(3);
+    state.queue = library(2);
+    state.current = Some(1);
+    state.playing = true;
+    state.playlists.insert("Mix".to_string(), library(1));
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    expect(&rx, "connection", |e| matches!(e,
+        Event::ToApp(AppEvent::MpdConnection(ConnectionState::Connected))))?;
+    expect(&rx, "database size", |e| matches!(e,
+        Event::ToApp(AppEvent::DatabaseLoading(3))))?;
+    expect(&rx, "database", |e| matches!(e,
+        Event::ToApp(AppEvent::Database(songs)) if songs == &library(3)))?;
+    expect(&rx, "end of database", |e| matches!(e,
+        Event::ToApp(AppEvent::DatabaseLoaded)))?;
+    expect(&rx, "queue", |e| matches!(e,
+        Event::ToAllComponents(ComponentEvent::Queue(q)) if q.len() == 2))?;
+    expect(&rx, "now playing", |e| matches!(e,
+        Event::ToApp(AppEvent::NowPlaying(Some(song)))
+            if song.title.as_deref() == Some("Song 1")))?;
+    expect(&rx, "status", |e| matches!(e,
+        Event::ToApp(AppEvent::Status(status))
+            if status.state == mpd::State::Play && status.queue_len == 2))?;
+    expect(&rx, "playlists", |e| matches!(e,
+        Event::ToApp(AppEvent::Playlist(pl))
+            if pl == &vec![Playlist { name: "Mix".to_string(), tracks: library(1) }]))?;
+
+    Ok(())
+}
+
+/* Sizes of the parts of the library sent, until it is done loading */
+fn database_chunks(rx: &mpsc::Receiver<Event>) -> Result<Vec<usize>, String> {
+    let mut chunks = Vec::new();
+
+    loop {
+        match expect(rx, "database", |e| matches!(e,
+            Event::ToApp(AppEvent::Database(_))
+            | Event::ToApp(AppEvent::DatabaseLoaded)))?
+        {
+            Event::ToApp(AppEvent::Database(songs)) => chunks.push(songs.len()),
+            _ => return Ok(chunks),
+        }
+    }
+}
+
+#[test]
+fn test_listener_loads_library_in_windows() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(2500);
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    // All under one directory, and still split up
+    assert_eq!(database_chunks(&rx)?, vec![1000, 1000, 500]);
+    assert!(mpd.state().log.contains(&"search \"any\" \"\" \"window\" \"2000:3000\"".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_reloads_after_partial_load() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(2500);
+    state.hang_up_on = Some("search any  window 1000:2000".to_string());
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    expect(&rx, "database size", |e| matches!(e,
+        Event::ToApp(AppEvent::DatabaseLoading(2500))))?;
+
+    // The first part arrives, then the load is dropped unfinished rather
+    // than reported as done
+    match expect(&rx, "lost connection", |e| matches!(e,
+        Event::ToApp(AppEvent::DatabaseLoaded)
+        | Event::ToApp(AppEvent::LostMpdConnection)))?
+    {
+        Event::ToApp(AppEvent::LostMpdConnection) => (),
+        e => return Err(format!("Expected the load to stop, got {:?}", e)),
+    }
+
+    expect(&rx, "database size", |e| matches!(e,
+        Event::ToApp(AppEvent::DatabaseLoading(2500))))?;
+    assert_eq!(database_chunks(&rx)?, vec![1000, 1000, 500]);
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_follows_idle() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(3);
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    mpd.wait_for_idle()?;
+
+    mpd.state().queue = library(3);
+    mpd.notify(&["playlist"]);
+
+    expect(&rx, "queue", |e| matches!(e,
+        Event::ToAllComponents(ComponentEvent::Queue(q)) if q.len() == 3))?;
+
+    mpd.wait_for_idle()?;
+
+    mpd.state().volume = 80;
+    mpd.notify(&["mixer"]);
+
+    expect(&rx, "status", |e| matches!(e,
+        Event::ToApp(AppEvent::Status(status)) if status.volume == 80))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_reconnects() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(3);
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    mpd.wait_for_idle()?;
+    mpd.drop_connections();
+
+    expect(&rx, "lost connection", |e| matches!(e,
+        Event::ToApp(AppEvent::LostMpdConnection)))?;
+    expect(&rx, "connection", |e| matches!(e,
+        Event::ToApp(AppEvent::MpdConnection(ConnectionState::Connected))))?;
+    expect(&rx, "database", |e| matches!(e,
+        Event::ToApp(AppEvent::Database(songs)) if songs.len() == 3))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_listener_logs_in() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.password = Some("hunter2".to_string());
+
+    let mpd = MockMpd::start(state);
+    let rx = start_listener(&mpd);
+
+    expect(&rx, "connection", |e| matches!(e,
+        Event::ToApp(AppEvent::MpdConnection(ConnectionState::Connected))))?;
+
+    assert_eq!(mpd.state().log.first(), Some(&"password \"hunter2\"".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_reports_its_connection() -> Result<(), String> {
+    let mpd = MockMpd::start(MockState::new());
+    let mut config = mpd.config();
+    config.password = Some("wrong".to_string());
+    mpd.state().password = Some("right".to_string());
+
+    let (tx, rx) = mpsc::channel();
+    let (mpd_tx, mpd_rx) = mpsc::channel();
+    mpd_sender::init_mpd_sender_thread(&config, mpd_rx, mpd_tx, tx);
+
+    let e = expect(&rx, "auth failure", |e| matches!(e,
+        Event::ToApp(AppEvent::MpdConnection(ConnectionState::AuthFailed(_)))))?;
+
+    match e {
+        Event::ToApp(AppEvent::MpdConnection(state)) =>
+            assert_eq!(state.to_string(), "Mpd rejected password. Retrying in 32s"),
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_runs_requests() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(3);
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, _rx) = start_sender(&mpd);
+
+    mpd_tx.send(MpdEvent::AddToQueue(
+        library(2).into_iter().map(|song| song.file).collect()
+    )).unwrap();
+    mpd_tx.send(MpdEvent::Play).unwrap();
+    mpd_tx.send(MpdEvent::Random).unwrap();
+    mpd_tx.send(MpdEvent::Volume(Adjustment::By(-100))).unwrap();
+    mpd_tx.send(MpdEvent::SavePlaylist("Saved".to_string())).unwrap();
+
+    mpd.wait_for(|state| state.playlists.contains_key("Saved"))?;
+
+    let state = mpd.state();
+    assert_eq!(state.queue, library(2));
+    assert!(state.playing);
+    assert!(state.random);
+    assert_eq!(state.volume, 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_reports_rejected_requests() -> Result<(), String> {
+    let mpd = MockMpd::start(MockState::new());
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    mpd_tx.send(
+        MpdEvent::RenamePlaylist("Missing".to_string(), "New".to_string())
+    ).unwrap();
+
+    expect(&rx, "error", |e| matches!(e,
+        Event::ToApp(AppEvent::Error(s))
+            if s.contains("rejected") && s.contains("No such playlist")))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_resends_after_connection_drops() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.queue = library(2);
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    mpd_tx.send(MpdEvent::Play).unwrap();
+    mpd.wait_for(|state| state.playing)?;
+
+    mpd.drop_connections();
+    mpd_tx.send(MpdEvent::Stop).unwrap();
+
+    expect(&rx, "resend", |e| matches!(e,
+        Event::ToApp(AppEvent::Error(s)) if s.contains("Resending")))?;
+
+    mpd.wait_for(|state| !state.playing)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_deletes_all_or_nothing() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.queue = library(5);
+    state.playlists.insert("Saved".to_string(), library(5));
+    state.hang_up_on = Some("delete 1".to_string());
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    // Hung up on after the first of the two deletes has been sent, so
+    // unless both run together, the resend deletes from a shifted queue
+    mpd_tx.send(MpdEvent::Delete(vec![1, 3])).unwrap();
+
+    expect(&rx, "resend", |e| matches!(e,
+        Event::ToApp(AppEvent::Error(s)) if s.contains("Resending")))?;
+
+    let remaining = vec![library(5)[0].clone(), library(5)[2].clone(), library(5)[4].clone()];
+    mpd.wait_for(|state| state.queue == remaining)?;
+
+    mpd.state().hang_up_on = Some("playlistdelete Saved 1".to_string());
+    mpd_tx.send(MpdEvent::PlaylistDelete("Saved".to_string(), vec![1, 3])).unwrap();
+
+    mpd.wait_for(|state| state.playlists["Saved"] == remaining)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_moves_all_or_nothing() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.queue = library(5);
+    state.playlists.insert("Saved".to_string(), library(5));
+    state.hang_up_on = Some("move 3 2".to_string());
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    mpd_tx.send(MpdEvent::MoveMany(vec![(1, 0), (3, 2)])).unwrap();
+
+    expect(&rx, "resend", |e| matches!(e,
+        Event::ToApp(AppEvent::Error(s)) if s.contains("Resending")))?;
+
+    let songs = library(5);
+    let moved = vec![&songs[1], &songs[0], &songs[3], &songs[2], &songs[4]];
+    mpd.wait_for(|state| state.queue.iter().eq(moved.iter().cloned()))?;
+
+    mpd.state().hang_up_on = Some("playlistmove Saved 3 2".to_string());
+    mpd_tx.send(MpdEvent::PlaylistMoveMany("Saved".to_string(), vec![(1, 0), (3, 2)])).unwrap();
+
+    mpd.wait_for(|state| state.playlists["Saved"].iter().eq(moved.iter().cloned()))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_plays_files_and_positions() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(3);
+    state.queue = library(1);
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, _rx) = start_sender(&mpd);
+
+    mpd_tx.send(MpdEvent::PlayFile(library(3)[2].file.clone())).unwrap();
+    mpd.wait_for(|state| state.playing && state.current == Some(1))?;
+    assert_eq!(mpd.state().queue[1], library(3)[2]);
+
+    mpd_tx.send(MpdEvent::PlayPos(0)).unwrap();
+    mpd.wait_for(|state| state.current == Some(0))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_lists_directories() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(2);
+    state.library.push(song("Loose.flac", "Loose"));
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    mpd_tx.send(MpdEvent::ListDirectory("Browser".to_string(), String::new())).unwrap();
+
+    expect(&rx, "root", |e| matches!(e,
+        Event::ToComponent(name, ComponentEvent::Directory(path, entries))
+            if name == "Browser" && path.is_empty() && entries == &vec![
+                DirEntry::Directory("Band".to_string()),
+                DirEntry::File("Loose.flac".to_string()),
+            ]))?;
+
+    mpd_tx.send(MpdEvent::AddPaths(vec!["Band".to_string()])).unwrap();
+    mpd.wait_for(|state| state.queue == library(2))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_sender_keeps_its_connections() -> Result<(), String> {
+    let mut state = MockState::new();
+    state.library = library(2);
+    state.password = Some("secret".to_string());
+
+    let mpd = MockMpd::start(state);
+    let (mpd_tx, rx) = start_sender(&mpd);
+
+    for _ in 0..3 {
+        mpd_tx.send(MpdEvent::ListDirectory("Browser".to_string(), "Band".to_string())).unwrap();
+        expect(&rx, "listing", |e| matches!(e,
+            Event::ToComponent(_, ComponentEvent::Directory(..))))?;
+    }
+
+    mpd_tx.send(MpdEvent::AddPaths(vec!["Band".to_string()])).unwrap();
+    mpd.wait_for(|state| state.queue == library(2))?;
+
+    assert_eq!(mpd.connections(), 2);
+    assert_eq!(mpd.state().log.iter().filter(|l| l.starts_with("password")).count(), 2);
+
+    // After a drop, both connections come back and the request goes through
+    mpd.drop_connections();
+    mpd_tx.send(MpdEvent::ListDirectory("Browser".to_string(), String::new())).unwrap();
+
+    expect(&rx, "listing", |e| matches!(e,
+        Event::ToComponent(_, ComponentEvent::Directory(path, _)) if path.is_empty()))?;
+
+    assert_eq!(mpd.connections(), 2);
+
+    Ok(())
+}
+
+fn config(host: Option<&str>, port: Option<&str>, password: Option<&str>) -> MpdConfig {
+    MpdConfig {
+        host: host.map(|s| s.to_string()),
+        port: port.map(|s| s.to_string()),
+        password: password.map(|s| s.to_string()),
+    }
+}
+
+#[test]
+fn test_config_set_host() -> Result<(), String> {
+    let mut c = MpdConfig::new();
+    c.set_host("example.com");
+    assert_eq!(c, config(Some("example.com"), None, None));
+
+    let mut c = MpdConfig::new();
+    c.set_host("secret@example.com");
+    assert_eq!(c, config(Some("example.com"), None, Some("secret")));
+
+    // Only the last @ splits, so passwords can have @ in them
+    let mut c = MpdConfig::new();
+    c.set_host("p@ss@/run/mpd/socket");
+    assert_eq!(c, config(Some("/run/mpd/socket"), None, Some("p@ss")));
+    assert!(c.is_socket());
+
+    // A leading @ is an abstract socket, not an empty password
+    let mut c = MpdConfig::new();
+    c.set_host("@mpd");
+    assert_eq!(c, config(Some("@mpd"), None, None));
+
+    let c = MpdConfig::from_vars(Some("secret@a".to_string()), Some("6601".to_string()));
+    assert_eq!(c, config(Some("a"), Some("6601"), Some("secret")));
+    assert_eq!((c.host(), c.port()), ("a", "6601"));
+
+    let c = MpdConfig::new();
+    assert_eq!((c.host(), c.port(), c.password()), ("127.0.0.1", "6600", None));
+
+    Ok(())
+}
+
+#[test]
+fn test_config_precedence() -> Result<(), String> {
+    let env = config(Some("a"), Some("6601"), Some("secret"));
+
+    // Each setting falls back on its own
+    assert_eq!(
+        config(None, Some("7000"), None).or(env.clone()),
+        config(Some("a"), Some("7000"), Some("secret")),
+    );
+    assert_eq!(
+        config(Some("a"), None, Some("mine")).or(env.clone()),
+        config(Some("a"), Some("6601"), Some("mine")),
+    );
+
+    // A password is never sent to a host it wasn't given for
+    assert_eq!(
+        config(Some("b"), None, None).or(env.clone()),
+        config(Some("b"), Some("6601"), None),
+    );
+
+    // A password set without a host goes with whichever host is used
+    assert_eq!(
+        config(Some("b"), None, None).or(config(None, None, Some("sbrc"))),
+        config(Some("b"), None, Some("sbrc")),
+    );
+
+    Ok(())
+}
@@ -17,8 +17,12 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
+use std::time::Duration;
+
 use mpd::Song;
 
+use crate::format;
+
 #[derive(Clone)]
 #[derive(Debug)]
 #[derive(PartialEq)]
@@ -26,3 +30,20 @@ pub struct Playlist {
     pub name: String,
     pub tracks: Vec<Song>,
 }
+
+impl Playlist {
+    /* Placeholder values for formatting a playlist for display */
+    pub fn placeholder(&self, key: &str) -> Option<String> {
+        match key {
+            "name" => Some(self.name.clone()),
+            "tracks" => Some(self.tracks.len().to_string()),
+            "duration" => Some(format::duration(Duration::from_secs(
+                self.tracks.iter()
+                    .filter_map(|t| t.duration)
+                    .map(|d| d.num_seconds().max(0) as u64)
+                    .sum()
+            ))),
+            _ => None,
+        }
+    }
+}
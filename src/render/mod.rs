@@ -0,0 +1,430 @@
+/* Off-screen drawing, so only what changed gets sent to the terminal
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use std::time::{Duration, Instant};
+
+use termion::{clear, color, cursor, style};
+use unicode_width::UnicodeWidthChar;
+
+use crate::color::Color;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug)]
+#[derive(Copy)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct Style {
+    pub fg: Color,
+    pub bold: bool,
+    pub underline: bool,
+    pub invert: bool,
+}
+
+impl Style {
+    pub fn new(fg: Color) -> Style {
+        Style {
+            fg,
+            bold: false,
+            underline: false,
+            invert: false,
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style::new(Color::Reset)
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct Cell {
+    // What is shown in the cell. Empty for the second half of a wide
+    // character
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[derive(Clone)]
+#[derive(PartialEq)]
+pub struct Grid {
+    w: u16,
+    h: u16,
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    pub fn new(w: u16, h: u16) -> Grid {
+        Grid {
+            w,
+            h,
+            cells: vec![Cell::default(); w as usize * h as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 { self.w }
+    pub fn height(&self) -> u16 { self.h }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        match x < self.w && y < self.h {
+            true => self.cells.get(y as usize * self.w as usize + x as usize),
+            false => None,
+        }
+    }
+
+    fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        match x < self.w && y < self.h {
+            true => self.cells.get_mut(y as usize * self.w as usize + x as usize),
+            false => None,
+        }
+    }
+
+    /* A surface covering the whole grid */
+    pub fn surface(&mut self) -> Surface<'_> {
+        let (w, h) = (self.w, self.h);
+
+        Surface {
+            grid: self,
+            x: 0,
+            y: 0,
+            w,
+            h,
+        }
+    }
+
+    /* The text of each row, without any styling */
+    pub fn lines(&self) -> Vec<String> {
+        self.cells.chunks(self.w.max(1) as usize)
+            .take(self.h as usize)
+            .map(|row| row.iter().map(|c| c.symbol.as_str()).collect())
+            .collect()
+    }
+}
+
+/* A rectangle of a grid to draw in. Coordinates start at 0 in the top left
+ * corner of the rectangle, and anything drawn outside of it is cut off */
+pub struct Surface<'a> {
+    grid: &'a mut Grid,
+    x: u16,
+    y: u16,
+    w: u16,
+    h: u16,
+}
+
+impl<'a> Surface<'a> {
+    pub fn width(&self) -> u16 { self.w }
+    pub fn height(&self) -> u16 { self.h }
+
+    /* Part of this surface */
+    pub fn area(&mut self, x: u16, y: u16, w: u16, h: u16) -> Surface<'_> {
+        let x = x.min(self.w);
+        let y = y.min(self.h);
+
+        Surface {
+            x: self.x + x,
+            y: self.y + y,
+            w: w.min(self.w - x),
+            h: h.min(self.h - y),
+            grid: self.grid,
+        }
+    }
+
+    /* Writes text starting at x, y. Returns how many columns it took up */
+    pub fn put(&mut self, x: u16, y: u16, text: &str, style: Style) -> u16 {
+        if y >= self.h {
+            return 0;
+        }
+
+        let mut col = x;
+
+        for c in text.chars() {
+            let cw = c.width().unwrap_or(0) as u16;
+
+            // Combining characters go in with whatever came before them
+            if cw == 0 {
+                if col > x {
+                    if let Some(cell) = self.cell(col - 1, y) {
+                        cell.symbol.push(c);
+                    }
+                }
+                continue;
+            }
+
+            if col + cw > self.w {
+                // Half of a wide character would be cut off, so pad instead
+                while col < self.w {
+                    self.set(col, y, " ", style);
+                    col += 1;
+                }
+                break;
+            }
+
+            self.set(col, y, &c.to_string(), style);
+
+            for i in 1..cw {
+                self.set(col + i, y, "", style);
+            }
+
+            col += cw;
+        }
+
+        col - x.min(col)
+    }
+
+    /* Writes each piece of text in its own color, one after another */
+    pub fn put_spans(&mut self, x: u16, y: u16, spans: &[(String, Color)], style: Style) -> u16 {
+        let mut col = x;
+
+        for (text, fg) in spans {
+            col += self.put(col, y, text, Style { fg: *fg, ..style });
+        }
+
+        col - x
+    }
+
+    /* Fills the whole surface with symbol */
+    pub fn fill(&mut self, symbol: &str, style: Style) {
+        for y in 0..self.h {
+            for x in 0..self.w {
+                self.set(x, y, symbol, style);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.fill(" ", Style::default());
+    }
+
+    /* Draws a box around the edge of the surface */
+    pub fn border(&mut self, style: Style) {
+        if self.w < 2 || self.h < 2 {
+            return;
+        }
+
+        let (right, bottom) = (self.w - 1, self.h - 1);
+
+        for x in 1..right {
+            self.set(x, 0, "─", style);
+            self.set(x, bottom, "─", style);
+        }
+
+        for y in 1..bottom {
+            self.set(0, y, "│", style);
+            self.set(right, y, "│", style);
+        }
+
+        self.set(0, 0, "┌", style);
+        self.set(right, 0, "┐", style);
+        self.set(0, bottom, "└", style);
+        self.set(right, bottom, "┘", style);
+    }
+
+    pub fn set(&mut self, x: u16, y: u16, symbol: &str, style: Style) {
+        if let Some(cell) = self.cell(x, y) {
+            cell.symbol = symbol.to_string();
+            cell.style = style;
+        }
+    }
+
+    fn cell(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        match x < self.w && y < self.h {
+            true => self.grid.get_mut(self.x + x, self.y + y),
+            false => None,
+        }
+    }
+}
+
+/* Components draw into the back grid. Flushing sends the terminal whatever
+ * differs from the front grid, which is what the terminal is showing */
+pub struct Renderer {
+    back: Grid,
+    // None when the terminal's contents are unknown, such as after a resize
+    front: Option<Grid>,
+}
+
+impl Renderer {
+    pub fn new(w: u16, h: u16) -> Renderer {
+        Renderer {
+            back: Grid::new(w, h),
+            front: None,
+        }
+    }
+
+    /* Part of the screen to draw in. x and y start at 1, like the terminal */
+    pub fn area(&mut self, x: u16, y: u16, w: u16, h: u16) -> Surface<'_> {
+        let x = x.saturating_sub(1).min(self.back.w);
+        let y = y.saturating_sub(1).min(self.back.h);
+
+        Surface {
+            x,
+            y,
+            w: w.min(self.back.w - x),
+            h: h.min(self.back.h - y),
+            grid: &mut self.back,
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.back.w, self.back.h)
+    }
+
+    /* What the screen will look like after the next flush. Lets everything
+     * be drawn without a terminal, such as in tests */
+    pub fn grid(&self) -> &Grid {
+        &self.back
+    }
+
+    /* Starts over at a new size. Everything is sent again on the next flush */
+    pub fn resize(&mut self, w: u16, h: u16) {
+        if (w, h) != self.size() {
+            self.back = Grid::new(w, h);
+            self.front = None;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.back.surface().clear();
+    }
+
+    /* Escape sequences that bring the terminal up to date with the back
+     * grid */
+    pub fn flush(&mut self) -> String {
+        let mut ret = String::new();
+
+        if self.front.is_none() {
+            ret.push_str(&format!("{}{}", style::Reset, clear::All));
+        }
+
+        let front = self.front.as_ref();
+        let mut pen: Option<Style> = None;
+        let mut at: Option<(u16, u16)> = None;
+
+        for y in 0..self.back.h {
+            for x in 0..self.back.w {
+                let cell = self.back.get(x, y).unwrap();
+
+                if cell.symbol.is_empty() || front.and_then(|f| f.get(x, y)) == Some(cell) {
+                    continue;
+                }
+
+                if at != Some((x, y)) {
+                    ret.push_str(&format!("{}", cursor::Goto(x + 1, y + 1)));
+                }
+
+                if pen != Some(cell.style) {
+                    ret.push_str(&style_escape(cell.style));
+                    pen = Some(cell.style);
+                }
+
+                ret.push_str(&cell.symbol);
+
+                let cw = cell.symbol.chars().next()
+                    .and_then(|c| c.width())
+                    .unwrap_or(1) as u16;
+                at = Some((x + cw.max(1), y));
+            }
+        }
+
+        if pen.is_some() {
+            ret.push_str(&format!("{}", style::Reset));
+        }
+
+        self.front = Some(self.back.clone());
+
+        ret
+    }
+}
+
+fn style_escape(s: Style) -> String {
+    let mut ret = format!("{}{}", style::Reset, color::Fg(s.fg));
+
+    if s.bold {
+        ret.push_str(&format!("{}", style::Bold));
+    }
+
+    if s.underline {
+        ret.push_str(&format!("{}", style::Underline));
+    }
+
+    if s.invert {
+        ret.push_str(&format!("{}", style::Invert));
+    }
+
+    ret
+}
+
+/* Paces frames so that however many changes come in at once, the terminal
+ * is written to at most once per interval */
+pub struct FrameTimer {
+    interval: Duration,
+    last: Option<Instant>,
+    pending: bool,
+}
+
+impl FrameTimer {
+    pub fn new(interval: Duration) -> FrameTimer {
+        FrameTimer {
+            interval,
+            last: None,
+            pending: false,
+        }
+    }
+
+    /* Asks for a frame as soon as the interval allows */
+    pub fn request(&mut self) {
+        self.pending = true;
+    }
+
+    /* Time left at now until the requested frame is due, or None if no
+     * frame has been requested */
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        if !self.pending {
+            return None;
+        }
+
+        match self.last {
+            Some(last) => Some((last + self.interval).saturating_duration_since(now)),
+            None => Some(Duration::ZERO),
+        }
+    }
+
+    pub fn due(&self, now: Instant) -> bool {
+        self.wait(now) == Some(Duration::ZERO)
+    }
+
+    /* Records that a frame was drawn at now */
+    pub fn frame(&mut self, now: Instant) {
+        self.pending = false;
+        self.last = Some(now);
+    }
+}
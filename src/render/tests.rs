@@ -0,0 +1,107 @@
+/* Tests for rendering
+   Copyright (C) 2020-2021 Sean DiGirolamo
+
+This file is part of Shellbird.
+
+Shellbird is free software; you can redistribute it and/or modify it
+under the terms of the GNU General Public License as published by the
+Free Software Foundation; either version 3, or (at your option) any
+later version.
+
+Shellbird is distributed in the hope that it will be useful, but
+WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU
+General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with Shellbird; see the file COPYING.  If not see
+<http://www.gnu.org/licenses/>.  */
+
+use super::*;
+
+#[test]
+fn test_put_clips_to_area() -> Result<(), String> {
+    let mut grid = Grid::new(6, 3);
+
+    {
+        let mut surface = grid.surface();
+        let mut area = surface.area(1, 1, 3, 5);
+
+        assert_eq!(area.height(), 2);
+        assert_eq!(area.put(0, 0, "abcdef", Style::default()), 3);
+        area.put(2, 1, "xyz", Style::default());
+        area.put(0, 2, "hidden", Style::default());
+    }
+
+    assert_eq!(grid.lines(), vec!["      ", " abc  ", "   x  "]);
+
+    Ok(())
+}
+
+#[test]
+fn test_put_wide_characters() -> Result<(), String> {
+    let mut grid = Grid::new(5, 1);
+
+    assert_eq!(grid.surface().put(0, 0, "a日本", Style::default()), 5);
+    assert_eq!(grid.get(2, 0).map(|c| c.symbol.as_str()), Some(""));
+
+    // Only half of 本 would fit
+    grid.surface().put(0, 0, "ab日本", Style::default());
+
+    assert_eq!(grid.lines(), vec!["ab日 "]);
+
+    Ok(())
+}
+
+#[test]
+fn test_flush_only_sends_changes() -> Result<(), String> {
+    let mut renderer = Renderer::new(10, 2);
+
+    renderer.area(1, 1, 10, 2).put(0, 0, "hello", Style::default());
+
+    let first = renderer.flush();
+    assert!(first.contains(&format!("{}", clear::All)));
+    assert!(first.contains("hello"));
+
+    assert_eq!(renderer.flush(), "");
+
+    renderer.area(1, 2, 10, 1).put(3, 0, "yo", Style::new(Color::Red));
+
+    let second = renderer.flush();
+    assert!(second.starts_with(&format!("{}", cursor::Goto(4, 2))));
+    assert!(second.contains("yo"));
+    assert!(!second.contains("hello"));
+    assert!(!second.contains(&format!("{}", clear::All)));
+
+    renderer.resize(8, 2);
+    assert!(renderer.flush().contains(&format!("{}", clear::All)));
+
+    Ok(())
+}
+
+#[test]
+fn test_frame_timer() -> Result<(), String> {
+    let interval = Duration::from_millis(16);
+    let start = Instant::now();
+    let mut frames = FrameTimer::new(interval);
+
+    assert_eq!(frames.wait(start), None);
+
+    frames.request();
+    assert!(frames.due(start));
+
+    frames.frame(start);
+    assert_eq!(frames.wait(start), None);
+
+    // Requests within an interval wait for the rest of it
+    frames.request();
+    frames.request();
+    assert!(!frames.due(start + Duration::from_millis(10)));
+    assert_eq!(
+        frames.wait(start + Duration::from_millis(10)),
+        Some(Duration::from_millis(6))
+    );
+    assert!(frames.due(start + interval));
+
+    Ok(())
+}
@@ -17,12 +17,17 @@ You should have received a copy of the GNU General Public License
 along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
-use std::collections::{VecDeque, HashMap};
+use std::collections::{VecDeque, HashMap, HashSet};
 use std::fmt;
 use crate::components::{Splitter, Components, Component, MoveFocusResult};
+use crate::render::Renderer;
 
 pub struct Screen {
     name: String,
+    // Components which have changed since the screen was last drawn
+    dirty: HashSet<String>,
+    // Everything needs drawing, such as after switching screens
+    all_dirty: bool,
 }
 
 impl fmt::Display for Screen {
@@ -35,6 +40,8 @@ impl Screen {
     pub fn new(name: &str) -> Screen {
         Screen {
             name: name.to_string(),
+            dirty: HashSet::new(),
+            all_dirty: true,
         }
     }
 
@@ -44,6 +51,71 @@ impl Screen {
 
     pub fn set(&mut self, name: &str) {
         self.name = name.to_string();
+        self.redraw_all();
+    }
+
+    /* Marks key to be redrawn on the next draw, if it is on this screen */
+    pub fn needs_redraw(
+        &mut self,
+        key: &str,
+        components: &HashMap<String, Components>
+    ) {
+        if !self.all_dirty && self.contains(key, components) {
+            self.dirty.insert(key.to_string());
+        }
+    }
+
+    pub fn redraw_all(&mut self) {
+        self.all_dirty = true;
+        self.dirty.clear();
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.all_dirty || !self.dirty.is_empty()
+    }
+
+    /* Draws whatever has changed since the last draw at x, y, w, h. A dirty
+     * component is drawn along with everything inside it, and anything
+     * outside of a dirty component is left alone */
+    pub fn draw(
+        &mut self,
+        (x, y, w, h): (u16, u16, u16, u16),
+        components: &HashMap<String, Components>,
+        renderer: &mut Renderer,
+    ) {
+        if !self.is_dirty() {
+            return;
+        }
+
+        let focus = self.focus(components);
+
+        if !components.contains_key(&self.name) {
+            renderer.area(x, y, w, h).clear();
+        } else if self.all_dirty {
+            draw_component(&self.name, (x, y, w, h), &focus, components, renderer);
+        } else {
+            self.draw_dirty(&self.name, (x, y, w, h), &focus, components, renderer);
+        }
+
+        self.all_dirty = false;
+        self.dirty.clear();
+    }
+
+    fn draw_dirty(
+        &self,
+        key: &str,
+        (x, y, w, h): (u16, u16, u16, u16),
+        focus: &str,
+        components: &HashMap<String, Components>,
+        renderer: &mut Renderer,
+    ) {
+        if self.dirty.contains(key) {
+            draw_component(key, (x, y, w, h), focus, components, renderer);
+        } else if let Some(Components::Splitter(splitter)) = components.get(key) {
+            for (key, x, y, w, h) in splitter.panels(x, y, w, h) {
+                self.draw_dirty(key, (x, y, w, h), focus, components, renderer);
+            }
+        }
     }
 
     pub fn focus<'a>(
@@ -115,6 +187,26 @@ impl Screen {
     }
 }
 
+/* Draws component key at x, y, w, h, and if it is a splitter, everything in
+ * it on top */
+pub fn draw_component(
+    key: &str,
+    (x, y, w, h): (u16, u16, u16, u16),
+    focus: &str,
+    components: &HashMap<String, Components>,
+    renderer: &mut Renderer,
+) {
+    if let Some(c) = components.get(key) {
+        c.draw(&mut renderer.area(x, y, w, h), focus == key);
+
+        if let Components::Splitter(splitter) = c {
+            for (key, x, y, w, h) in splitter.panels(x, y, w, h) {
+                draw_component(key, (x, y, w, h), focus, components, renderer);
+            }
+        }
+    }
+}
+
 fn splitter_contains(
     component: &Components,
     key: &str,
@@ -18,11 +18,8 @@ along with Shellbird; see the file COPYING.  If not see
 <http://www.gnu.org/licenses/>.  */
 
 use std::{fs, thread, sync::mpsc};
-use std::collections::HashMap;
 use std::io::{self, BufReader, BufRead};
 
-use mpd::Song;
-
 use crate::event::*;
 
 pub fn load_style_tree_async(path: &str, tx: mpsc::Sender<Event>) {
@@ -59,7 +56,6 @@ impl Style {
 #[derive(Debug)]
 pub struct StyleTree {
     styles: Vec<Style>,
-    tracks: HashMap<Option<String>, Vec<Song>>,
 }
 
 impl StyleTree {
@@ -72,7 +68,6 @@ impl StyleTree {
                     children: Vec::new(),
                 }
             ],
-            tracks: HashMap::new(),
         }
     }
 
@@ -103,31 +98,6 @@ impl StyleTree {
             ret
         }
     }
-
-    pub fn set_tracks(&mut self, tracks: Vec<Song>) {
-        self.tracks.clear();
-
-        for track in tracks {
-            let key = match track.tags.get("Genre") {
-                Some(s) => Some(s.to_string()),
-                None => None,
-            };
-
-            let bucket = match self.tracks.get_mut(&key) {
-                Some(vec) => vec,
-                None => {
-                    self.tracks.insert(key.clone(), Vec::new());
-                    self.tracks.get_mut(&key).unwrap()
-                },
-            };
-
-            bucket.push(track);
-        }
-    }
-
-    pub fn tracks(&self, key: &Option<String>) -> Option<&Vec<Song>> {
-        self.tracks.get(key)
-    }
 }
 
 pub fn load_tree_from_file(path: &str) -> Result<StyleTree, io::Error> {